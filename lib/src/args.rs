@@ -12,6 +12,9 @@ pub struct SirgovanArgs {
 pub enum Commands {
 	/// Run a tournament
 	Tournament(TournamentArgs),
+
+	/// Manage the halls
+	Halls(HallsArgs),
 }
 
 #[derive(Args)]
@@ -137,3 +140,23 @@ pub struct TournamentFinish {
 	/// Number of the last round
 	pub round: u64,
 }
+
+#[derive(Args)]
+pub struct HallsArgs {
+	#[command(subcommand)]
+	pub command: HallsCommand,
+}
+
+#[derive(Subcommand)]
+pub enum HallsCommand {
+	/// Rebuild the hall records of a server from its hall channels
+	///
+	/// Scans every configured hall channel, re-fetches the reactions on the originals and reports the entries whose originals were deleted
+	Backfill(HallsBackfill),
+}
+
+#[derive(Args)]
+pub struct HallsBackfill {
+	/// ID of the server whose halls to rebuild
+	pub guild: u64,
+}
@@ -1,3 +1,4 @@
+pub mod digest;
 pub mod fake_iasip;
 pub mod fake_twitter;
 pub mod halls;
@@ -5,6 +6,7 @@ pub mod no_context;
 pub mod periodic;
 pub mod randomize_self;
 pub mod shutdown;
+pub mod throwback;
 
 use serenity::client::Cache;
 use serenity::gateway::ShardManager;
@@ -18,6 +20,8 @@ use super::data::BotData;
 use super::helpers::react_locks::ReactSafety;
 use super::helpers::screenshotter::Screenshotter;
 use crate::prelude::{govanerror, GovanResult};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 pub struct Bot {
@@ -30,6 +34,12 @@ pub struct Bot {
 	pub(crate) shutdown: Mutex<bool>,
 	pub(crate) screenshotter: RwLock<Option<Screenshotter>>,
 	pub(crate) periodic: Mutex<Periodic>,
+	/// Who has the no-context role in each guild, as far as I know
+	pub(crate) no_context_holders: Mutex<HashMap<u64, HashSet<u64>>>,
+	/// Whether the no-context holders were found out already, it's done once a run
+	pub(crate) no_context_reconciled: AtomicBool,
+	/// Thresholds of scaling halls for each message, and when they were worked out
+	pub(crate) hall_thresholds: Mutex<HashMap<(u64, String), (std::time::Instant, usize)>>,
 }
 
 impl Bot {
@@ -47,6 +57,9 @@ impl Bot {
 			shutdown: Mutex::new(false),
 			screenshotter: RwLock::new(None),
 			periodic: Mutex::new(Periodic::new()),
+			no_context_holders: Mutex::new(HashMap::new()),
+			no_context_reconciled: AtomicBool::new(false),
+			hall_thresholds: Mutex::new(HashMap::new()),
 		}
 	}
 
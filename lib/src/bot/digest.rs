@@ -0,0 +1,232 @@
+use super::Bot;
+use crate::data::halls::HallFilter;
+use crate::data::servers::DigestPeriod;
+use crate::data::Digest;
+use crate::helpers::handlebars::{DigestData, DigestEntryData, DigestRankData};
+use crate::prelude::*;
+
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveTime, Utc};
+use serenity::builder::{CreateAttachment, CreateMessage};
+use serenity::model::prelude::*;
+
+/// People shown in each ranking
+const DIGEST_RANKS: usize = 5;
+
+/// The last period that's over by the end of `today`: the day it started, the day after it
+/// ended, and what it's called
+fn last_period(period: DigestPeriod, today: NaiveDate) -> (NaiveDate, NaiveDate, String) {
+	match period {
+		DigestPeriod::Weekly => {
+			let until = today - Days::new(today.weekday().num_days_from_monday() as u64);
+			let from = until - Days::new(7);
+			(
+				from,
+				until,
+				format!("Week of {}", from.format("%B %-d, %Y")),
+			)
+		}
+		DigestPeriod::Monthly => {
+			let until = today.with_day(1).unwrap_or(today);
+			let from = until - Months::new(1);
+			(from, until, from.format("%B %Y").to_string())
+		}
+	}
+}
+
+impl Bot {
+	/// Posts the hall digests that are due, each one once a period as soon as it's past their
+	/// time on the day the period ends
+	pub async fn maybe_digest(&self) {
+		let now = Utc::now();
+
+		let due = {
+			let data = self.data().await;
+			data.servers
+				.values()
+				.flat_map(|server| {
+					let data = &data;
+					server.halls.iter().filter_map(move |hall| {
+						let digest = hall.digest.as_ref()?;
+						let time = NaiveTime::from_hms_opt(digest.hour, digest.minute, 0)?;
+
+						// Until it's time on the day a period ends, the one before is the last
+						let mut period = last_period(digest.period, now.date_naive());
+						if now.naive_utc() < period.1.and_time(time) {
+							period = last_period(digest.period, period.1 - Days::new(1));
+						}
+
+						(data.schedule.last_digest(server.id, &hall.name) != Some(period.0))
+							.then(|| (server.id, hall.name.clone(), digest.clone(), period))
+					})
+				})
+				.collect::<Vec<_>>()
+		};
+
+		for (guild, hall, digest, (from, until, period)) in due {
+			// If it couldn't be posted, it's tried again next time
+			if let Err(e) = self
+				.digest(guild, &hall, &digest, from, until, period)
+				.await
+			{
+				e.log();
+				continue;
+			}
+
+			let posted = self
+				.data
+				.write()
+				.await
+				.schedule
+				.digest_posted(guild, &hall, from)
+				.await;
+			if let Err(e) = posted {
+				e.log();
+			}
+		}
+	}
+
+	/// Sums up the entries a hall got from `from` until the day before `until`, as an image.
+	/// A period without entries has nothing to sum up, which is fine
+	async fn digest(
+		&self,
+		guild: u64,
+		hall: &str,
+		digest: &Digest,
+		from: NaiveDate,
+		until: NaiveDate,
+		period: String,
+	) -> GovanResult {
+		let start = |date: NaiveDate| date.and_time(NaiveTime::MIN).and_utc().timestamp();
+		let filter = HallFilter {
+			hall: Some(hall.to_string()),
+			since: Some(start(from)),
+			until: Some(start(until)),
+			..Default::default()
+		};
+
+		let (stats, top) = {
+			let data = self.data().await;
+			let top = data
+				.hall_entries
+				.filtered(guild, &filter)
+				.max_by_key(|e| (e.reactors.len(), e.message))
+				.cloned();
+			(data.hall_entries.stats(guild, &filter), top)
+		};
+
+		let Some(top) = top else {
+			logger::debug_fmt!("No entries in {} for the digest of {}", hall, guild);
+			return Ok(());
+		};
+
+		let cache = self.cache().await;
+		let http = self.http().await;
+		let users = stats
+			.authors
+			.iter()
+			.take(DIGEST_RANKS)
+			.chain(stats.reactors.iter().take(DIGEST_RANKS))
+			.map(|(id, _)| *id)
+			.chain(top.author)
+			.collect::<Vec<_>>();
+		let names =
+			util::member_names((&cache, http.as_ref()), Some(GuildId::new(guild)), &users).await;
+		let ranks = |ranked: &[(u64, usize)]| {
+			ranked
+				.iter()
+				.take(DIGEST_RANKS)
+				.map(|(id, count)| DigestRankData {
+					name: names[id].clone(),
+					count: *count,
+				})
+				.collect::<Vec<_>>()
+		};
+
+		// The stored text is enough without the original, the picture and avatar aren't
+		let original = if top.deleted {
+			None
+		} else {
+			ChannelId::new(top.channel)
+				.message(&http, MessageId::new(top.message))
+				.await
+				.ok()
+		};
+
+		let data = DigestData {
+			hall: hall.to_string(),
+			period: period.clone(),
+			entries: stats.entries,
+			top: Some(DigestEntryData {
+				author: top.author.map_or_else(
+					|| util::UNKNOWN_USER.to_string(),
+					|author| names[&author].clone(),
+				),
+				avatar: original.as_ref().map(|msg| msg.author.face()),
+				content: top.content.clone(),
+				reactions: top.reactors.len(),
+				image: original.as_ref().and_then(|msg| {
+					msg.attachments
+						.iter()
+						.find(|a| {
+							a.content_type
+								.as_deref()
+								.is_some_and(|kind| kind.starts_with("image/"))
+						})
+						.map(|a| a.url.clone())
+				}),
+			}),
+			authors: ranks(&stats.authors),
+			reactors: ranks(&stats.reactors),
+		};
+
+		let image = self.screenshotter().await?.digest(data).await?;
+
+		ChannelId::new(digest.channel)
+			.send_message(
+				&http,
+				CreateMessage::default()
+					.content(format!(
+						"**{}** in the {} hall, most reacted: https://discord.com/channels/{}/{}/{}",
+						period, hall, guild, top.hall_channel, top.hall_message
+					))
+					.add_file(CreateAttachment::bytes(image, "digest.png")),
+			)
+			.await?;
+
+		Ok(())
+	}
+}
+
+#[test]
+fn digest_periods() {
+	let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+	let period = |from, until, label: &str| (from, until, label.to_string());
+
+	// 2023-10-02 is a Monday
+	let week = period(
+		date(2023, 9, 25),
+		date(2023, 10, 2),
+		"Week of September 25, 2023",
+	);
+	assert_eq!(last_period(DigestPeriod::Weekly, date(2023, 10, 2)), week);
+	assert_eq!(last_period(DigestPeriod::Weekly, date(2023, 10, 5)), week);
+	assert_eq!(
+		last_period(DigestPeriod::Weekly, date(2023, 10, 1)),
+		period(
+			date(2023, 9, 18),
+			date(2023, 9, 25),
+			"Week of September 18, 2023"
+		)
+	);
+
+	let month = period(date(2023, 9, 1), date(2023, 10, 1), "September 2023");
+	assert_eq!(last_period(DigestPeriod::Monthly, date(2023, 10, 1)), month);
+	assert_eq!(
+		last_period(DigestPeriod::Monthly, date(2023, 10, 15)),
+		month
+	);
+	assert_eq!(
+		last_period(DigestPeriod::Monthly, date(2024, 1, 10)),
+		period(date(2023, 12, 1), date(2024, 1, 1), "December 2023")
+	);
+}
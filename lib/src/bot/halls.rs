@@ -3,23 +3,86 @@ use std::ops::Not;
 use crate::prelude::*;
 
 use crate::bot::Bot;
-use crate::data::EmojiType;
+use crate::data::halls::HallEntry;
+use crate::data::servers::{HallColor, HallEmbed, OnDelete};
+use crate::data::{EmojiType, Hall};
+use crate::helpers::react_locks::Requirement;
 
-use serenity::builder::{CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, CreateMessage};
+use serenity::builder::{
+	CreateAttachment, CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, CreateMessage,
+	EditMessage, GetMessages,
+};
 use serenity::model::prelude::*;
 use serenity::prelude::*;
 
+use crate::other_utils::tournaments::FOOTER_PIN;
 use crate::util::random;
 
+use std::collections::HashSet;
+
+const FALLBACK: &str = "https://cdn.jsdelivr.net/gh/jdecked/twemoji/assets/72x72/2049.png";
+
+/// Biggest file I can upload to a guild without boosts, bigger videos and audio are only
+/// linked to
+const MAX_UPLOAD: u32 = 10 * 1024 * 1024;
+/// Discord won't take more in one message
+const MAX_EMBEDS: usize = 10;
+/// Characters of a replied-to message to show
+const REPLY_SNIPPET: usize = 100;
+/// Characters of reactor names to show, the rest are counted
+const REACTORS_LENGTH: usize = 900;
+/// How long the threshold of a scaling hall holds before the channel is looked at again
+const THRESHOLD_CACHE: std::time::Duration = std::time::Duration::from_secs(120);
+/// Name of the field with the teleport link, which posts only have when the message had text
+const TELEPORT_FIELD: &str = "\u{200b}";
+
 impl Bot {
+	/// Reactions a message needs to get into a hall, counting how busy its channel was before
+	/// it. That's only looked at again once the last look is a couple minutes old
+	pub async fn hall_threshold(&self, ctx: &Context, msg: &Message, hall: &Hall) -> usize {
+		let Some(scaling) = hall.scaling.as_ref() else {
+			return hall.threshold;
+		};
+
+		let key = (msg.id.get(), hall.name.clone());
+		if let Some((when, threshold)) = self.hall_thresholds.lock().await.get(&key) {
+			if when.elapsed() < THRESHOLD_CACHE {
+				return *threshold;
+			}
+		}
+
+		let since = msg.timestamp.unix_timestamp() - scaling.minutes as i64 * 60;
+		let recent = match msg
+			.channel_id
+			.messages(ctx, GetMessages::new().before(msg.id).limit(100))
+			.await
+		{
+			Ok(messages) => messages
+				.iter()
+				.filter(|m| m.timestamp.unix_timestamp() >= since)
+				.count(),
+			Err(e) => {
+				logger::error_fmt!("Could not check activity in {}: {}", msg.channel_id, e);
+				0
+			}
+		};
+
+		let threshold = scaling.threshold(hall.threshold, recent);
+		let mut thresholds = self.hall_thresholds.lock().await;
+		thresholds.retain(|_, (when, _)| when.elapsed() < THRESHOLD_CACHE);
+		thresholds.insert(key, (std::time::Instant::now(), threshold));
+		threshold
+	}
+
 	pub async fn maybe_pin(
 		&self,
 		ctx: &Context,
 		msg: Message,
 		reaction: &Reaction,
 		dest: GuildChannel,
-		override_icon: Option<EmojiType>,
-	) -> GovanResult {
+		hall: &Hall,
+		reactors: &[u64],
+	) -> GovanResult<Message> {
 		let perms = dest.permissions_for_user(ctx, ctx.cache.current_user().id)?;
 
 		if !perms.send_messages() {
@@ -29,113 +92,660 @@ impl Bot {
 			));
 		}
 
-		const FALLBACK: &str = "https://cdn.jsdelivr.net/gh/jdecked/twemoji/assets/72x72/2049.png";
+		let icon_url = if let Some(emoji) = hall.icon.clone() {
+			match emoji {
+				EmojiType::Unicode(ref emoji) => util::url_from_unicode_emoji(emoji),
+				EmojiType::Discord(id) => util::url_from_discord_emoji(id, false),
+			}
+		} else {
+			match reaction.emoji {
+				ReactionType::Unicode(ref emoji) => util::url_from_unicode_emoji(emoji),
+				ReactionType::Custom { animated, id, .. } => {
+					util::url_from_discord_emoji(id.into(), animated)
+				}
+				_ => FALLBACK.to_string(),
+			}
+		};
 
-		let pin_data = PinData {
-			icon_url: if let Some(emoji) = override_icon {
-				match emoji {
-					EmojiType::Unicode(ref emoji) => util::url_from_unicode_emoji(emoji),
-					EmojiType::Discord(id) => util::url_from_discord_emoji(id, false),
+		let color = match hall.embed.color {
+			HallColor::Random => random_color(),
+			HallColor::Role => match dest.guild_id.member(ctx, msg.author.id).await {
+				Ok(member) => member
+					.colour(ctx)
+					.map_or_else(random_color, |colour| colour.0),
+				Err(e) => {
+					logger::error_fmt!("Could not get the colour of {}: {}", msg.author.id, e);
+					random_color()
 				}
-			} else {
-				match reaction.emoji {
-					ReactionType::Unicode(ref emoji) => util::url_from_unicode_emoji(emoji),
-					ReactionType::Custom { animated, id, .. } => {
-						util::url_from_discord_emoji(id.into(), animated)
+			},
+			HallColor::Fixed(color) => color,
+		};
+
+		let reactors = reactor_names(ctx, dest.guild_id, reactors).await;
+		let pin_data = PinData::new(
+			msg,
+			&hall.name,
+			hall.embed.clone(),
+			icon_url,
+			color,
+			reactors,
+		);
+
+		let limit = upload_limit(ctx, dest.guild_id);
+		let pin = self.make_pin(ctx, &pin_data, limit).await;
+
+		match dest.send_message(&ctx, pin).await {
+			Ok(pin) => Ok(pin),
+			// Discord may not take the files after all, links will have to do
+			Err(e) if pin_data.media.iter().any(|m| m.size <= limit) => {
+				logger::error_fmt!(
+					"Could not post {} with its files: {}",
+					pin_data.message_url,
+					e
+				);
+				let pin = self.make_pin(ctx, &pin_data, 0).await;
+				Ok(dest.send_message(&ctx, pin).await?)
+			}
+			Err(e) => Err(e.into()),
+		}
+	}
+
+	/// Rebuilds the hall posts of a message after it's been edited
+	pub async fn update_pins(&self, ctx: &Context, msg: Message) -> GovanResult {
+		let entries = {
+			let data = self.data().await;
+			data.hall_entries
+				.of_message(msg.id.get())
+				.iter()
+				.filter(|entry| !entry.deleted && !entry.retracted)
+				.map(|entry| {
+					let style = data
+						.servers
+						.get(&entry.guild)
+						.and_then(|server| server.hall(&entry.hall))
+						.map(|hall| hall.embed.clone())
+						.unwrap_or_default();
+					(entry.clone(), style)
+				})
+				.collect::<Vec<_>>()
+		};
+
+		// One post that can't be updated doesn't hold up the others
+		for (entry, style) in entries {
+			self.update_pin(ctx, &msg, &entry, style)
+				.await
+				.log_if_err(&format!(
+					"Could not update hall post {}",
+					entry.hall_message
+				));
+		}
+
+		Ok(())
+	}
+
+	async fn update_pin(
+		&self,
+		ctx: &Context,
+		msg: &Message,
+		entry: &HallEntry,
+		style: HallEmbed,
+	) -> GovanResult {
+		let channel = ChannelId::new(entry.hall_channel);
+		let mut pin = channel
+			.message(ctx, MessageId::new(entry.hall_message))
+			.await?;
+
+		// Keep the look it was posted with
+		let Some(old) = pin.embeds.first() else {
+			return Ok(());
+		};
+		let icon_url = old
+			.footer
+			.as_ref()
+			.and_then(|footer| footer.icon_url.clone())
+			.unwrap_or_else(|| FALLBACK.to_string());
+		let color = old.colour.map_or(0, |colour| colour.0);
+
+		// Media isn't uploaded again, whatever made it the first time is still there
+		let uploaded = pin
+			.attachments
+			.iter()
+			.map(|a| a.filename.clone())
+			.collect::<Vec<_>>();
+
+		let reactors = reactor_names(ctx, GuildId::new(entry.guild), &entry.reactors).await;
+		let data = PinData::new(msg.clone(), &entry.hall, style, icon_url, color, reactors);
+		let embeds = self.make_pin_embeds(&data, &uploaded);
+		pin.edit(ctx, EditMessage::new().embeds(embeds)).await?;
+
+		self.data
+			.write()
+			.await
+			.hall_entries
+			.update(entry.message, &entry.hall, |entry| {
+				entry.content = msg.content.clone()
+			})
+			.await
+	}
+
+	/// Marks or removes the hall posts of a message that was deleted, depending on each hall
+	pub async fn unpin_deleted(&self, ctx: &Context, message: MessageId) -> GovanResult {
+		let entries = {
+			let data = self.data().await;
+			data.hall_entries
+				.of_message(message.get())
+				.iter()
+				.filter(|entry| !entry.deleted)
+				.map(|entry| {
+					let on_delete = data
+						.servers
+						.get(&entry.guild)
+						.and_then(|server| server.hall(&entry.hall))
+						.map_or(OnDelete::default(), |hall| hall.on_delete);
+					(entry.clone(), on_delete)
+				})
+				.collect::<Vec<_>>()
+		};
+
+		// One post that can't be marked or removed doesn't hold up the others
+		for (entry, on_delete) in entries {
+			self.unpin_entry(ctx, &entry, on_delete)
+				.await
+				.log_if_err(&format!("Could not unpin hall post {}", entry.hall_message));
+		}
+
+		Ok(())
+	}
+
+	async fn unpin_entry(
+		&self,
+		ctx: &Context,
+		entry: &HallEntry,
+		on_delete: OnDelete,
+	) -> GovanResult {
+		let channel = ChannelId::new(entry.hall_channel);
+		let pin_id = MessageId::new(entry.hall_message);
+
+		match on_delete {
+			OnDelete::Keep => {
+				self.mark_pin(ctx, channel, pin_id, "[Deleted]").await?;
+
+				self.data
+					.write()
+					.await
+					.hall_entries
+					.update(entry.message, &entry.hall, |entry| entry.deleted = true)
+					.await
+			}
+			OnDelete::Remove => {
+				// A post someone already deleted by hand only needs its entry gone
+				match channel.delete_message(ctx, pin_id).await {
+					Err(e) if !util::is_discord_error(&e, &[util::UNKNOWN_MESSAGE]) => {
+						return Err(e.into())
 					}
-					_ => FALLBACK.to_string(),
+					_ => (),
 				}
-			},
-			r: random::from_range(0..0x10) * 0x10,
-			g: random::from_range(0..0x10) * 0x10,
-			b: random::from_range(0..0x10) * 0x10,
-			message_url: msg.link(),
-			author: msg.author.name.clone(),
-			author_avatar: msg
-				.author
-				.avatar_url()
-				.clone()
-				.unwrap_or(msg.author.default_avatar_url()),
-			content: msg.content.is_empty().not().then_some(msg.content),
-			timestamp: msg.timestamp,
-			message_id: msg.id.get(),
-			channel_id: msg.channel_id.get(),
-			embed: if let [ref first, ..] = &msg.attachments[..] {
-				let content_type = first.content_type.as_ref();
-				if content_type.is_some_and(|x| x.starts_with("video/")) {
-					Embed::Video(first.filename.clone())
-				} else if content_type.is_some_and(|x| x.starts_with("audio/")) {
-					Embed::Audio(first.filename.clone())
-				} else {
-					Embed::Image(first.url.clone())
+
+				self.data
+					.write()
+					.await
+					.hall_entries
+					.remove(entry.message, &entry.hall)
+					.await
+			}
+		}
+	}
+
+	/// Takes a message back out of the halls it got into with this emoji, if it doesn't have
+	/// enough reactions anymore and it's still early enough to
+	pub async fn maybe_retract(
+		&self,
+		ctx: &Context,
+		reaction: &Reaction,
+		msg: &Message,
+	) -> GovanResult {
+		let emoji = EmojiType::from(&reaction.emoji);
+		let now = chrono::Utc::now().timestamp();
+
+		let candidates = {
+			let data = self.data().await;
+			data.hall_entries
+				.of_message(reaction.message_id.get())
+				.iter()
+				.filter(|entry| !entry.deleted && !entry.retracted)
+				.filter(|entry| entry.emoji.as_ref() == Some(&emoji))
+				.filter_map(|entry| {
+					let hall = data.servers.get(&entry.guild)?.hall(&entry.hall)?;
+					let retraction = hall.retraction.as_ref()?;
+
+					(now - entry.time < retraction.minutes as i64 * 60)
+						.then(|| (entry.clone(), hall.clone(), retraction.remove))
+				})
+				.collect::<Vec<_>>()
+		};
+
+		if candidates.is_empty() {
+			return Err(govanerror::debug!(
+				// log = "Nothing to retract"
+			));
+		}
+
+		for (entry, hall, remove) in candidates {
+			let required = Requirement {
+				amount: self.hall_threshold(ctx, msg, &hall).await,
+				self_reactions: hall.self_reactions,
+				excluded_roles: &hall.excluded_roles,
+			};
+
+			let reactors = self
+				.pin_lock()
+				.await
+				.reactors(ctx, msg, reaction, &required)
+				.await?;
+
+			if reactors.len() >= required.amount {
+				continue;
+			}
+
+			logger::info_fmt!("Retracting {} from hall {}", msg.id, hall.name);
+
+			let channel = ChannelId::new(entry.hall_channel);
+			let pin_id = MessageId::new(entry.hall_message);
+
+			if remove {
+				channel.delete_message(ctx, pin_id).await?;
+
+				// Without my reaction or its entry, it can get in again
+				msg.delete_reaction(ctx, None, reaction.emoji.clone())
+					.await?;
+				self.data
+					.write()
+					.await
+					.hall_entries
+					.remove(entry.message, &entry.hall)
+					.await?;
+			} else {
+				self.mark_pin(ctx, channel, pin_id, "[Retracted]").await?;
+
+				self.data
+					.write()
+					.await
+					.hall_entries
+					.update(entry.message, &entry.hall, |entry| entry.retracted = true)
+					.await?;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Records every post in the hall channels of a guild, going by the entry I have of it or
+	/// the footer every hall post had before footers could be changed. Only posts that aren't
+	/// recorded yet, unless `rebuild` is set: then every entry of the halls with a channel is
+	/// made again from what's in the channels, with fresh reactors
+	pub async fn backfill_halls(
+		&self,
+		ctx: &Context,
+		guild: u64,
+		rebuild: bool,
+	) -> GovanResult<BackfillReport> {
+		let halls = self
+			.data()
+			.await
+			.servers
+			.get(&guild)
+			.map(|server| server.halls.clone())
+			.ok_or_else(govanerror::error_lazy!(
+				log fmt = ("Backfilling halls of unknown guild {}", guild),
+				user = "I don't know this server"
+			))?;
+
+		// Halls can share a channel, which is only looked through once
+		let mut channels: Vec<(u64, Vec<&Hall>)> = Vec::new();
+		for hall in halls.iter().filter(|hall| hall.channel != 0) {
+			match channels
+				.iter_mut()
+				.find(|(channel, _)| *channel == hall.channel)
+			{
+				Some((_, shared)) => shared.push(hall),
+				None => channels.push((hall.channel, vec![hall])),
+			}
+		}
+
+		let me = ctx.cache.current_user().id;
+		let mut entries = Vec::new();
+		let mut found = HashSet::new();
+		let mut unknown = 0;
+
+		for (channel, shared) in channels.iter() {
+			let channel = ChannelId::new(*channel);
+			let mut before: Option<MessageId> = None;
+
+			loop {
+				let mut request = GetMessages::new().limit(100);
+				if let Some(before) = before {
+					request = request.before(before);
 				}
-			} else if let [ref first, ..] = &msg.embeds[..] {
-				match first.image.as_ref() {
-					Some(url) => Embed::Image(url.url.clone()),
-					None => match first.thumbnail.as_ref() {
-						Some(thumb) => Embed::Image(thumb.url.clone()),
-						None => Embed::Nothing,
-					},
+
+				let batch = channel.messages(ctx, request).await?;
+				let Some(last) = batch.last() else {
+					break;
+				};
+				before = Some(last.id);
+
+				for pin in batch.iter().filter(|m| m.author.id == me) {
+					let known = self
+						.data()
+						.await
+						.hall_entries
+						.of_post(pin.id.get())
+						.filter(|entry| shared.iter().any(|hall| hall.name == entry.hall))
+						.cloned();
+
+					let (hall, message, original_channel) = match &known {
+						Some(entry) => {
+							let Some(hall) = shared.iter().find(|hall| hall.name == entry.hall)
+							else {
+								continue;
+							};
+							(*hall, entry.message, entry.channel)
+						}
+						None => {
+							let Some((message, original_channel)) = pin_origin(pin) else {
+								continue;
+							};
+							// Without an entry, there's no telling which of the halls it's from
+							let [hall] = shared[..] else {
+								unknown += 1;
+								continue;
+							};
+							(hall, message, original_channel)
+						}
+					};
+
+					if (known.is_some() && !rebuild) || !found.insert((message, hall.name.clone()))
+					{
+						continue;
+					}
+
+					let mut entry = self
+						.backfilled_entry(ctx, guild, hall, pin, message, original_channel)
+						.await;
+
+					// What happened while I was keeping track is still true
+					if let Some(known) = known {
+						entry.author = entry.author.or(known.author);
+						entry.retracted = known.retracted;
+						entry.deleted |= known.deleted;
+						entry.time = known.time;
+					}
+
+					entries.push(entry);
 				}
-			} else if let [ref first, ..] = &msg.sticker_items[..] {
-				first
-					.image_url()
-					.map_or_else(|| Embed::Nothing, Embed::Image)
-			} else {
-				Embed::Nothing
-			},
+			}
+		}
+
+		let report = BackfillReport {
+			entries: entries.len(),
+			deleted: entries.iter().filter(|e| e.deleted).cloned().collect(),
+			unknown,
+		};
+
+		let mut data = self.data.write().await;
+		if rebuild {
+			let rebuilt = channels
+				.iter()
+				.flat_map(|(_, shared)| shared.iter().map(|hall| hall.name.clone()))
+				.collect();
+			data.hall_entries
+				.replace_halls(guild, &rebuilt, entries)
+				.await?;
+		} else {
+			data.hall_entries.record_all(entries).await?;
+		}
+
+		Ok(report)
+	}
+
+	/// An entry for a post that was made without being recorded. The reactors are whoever
+	/// reacted with the emoji I reacted with
+	async fn backfilled_entry(
+		&self,
+		ctx: &Context,
+		guild: u64,
+		hall: &Hall,
+		pin: &Message,
+		message: u64,
+		channel: u64,
+	) -> HallEntry {
+		let mut entry = HallEntry {
+			hall: hall.name.clone(),
+			guild,
+			channel,
+			message,
+			author: None,
+			hall_channel: pin.channel_id.get(),
+			hall_message: pin.id.get(),
+			reactors: Vec::new(),
+			time: pin.timestamp.unix_timestamp(),
+			content: pin_content(pin).unwrap_or_default(),
+			emoji: None,
+			deleted: false,
+			retracted: false,
+		};
+
+		let original = match ChannelId::new(channel)
+			.message(ctx, MessageId::new(message))
+			.await
+		{
+			Ok(original) => original,
+			Err(_) => {
+				entry.deleted = true;
+				return entry;
+			}
 		};
 
-		dest.send_message(&ctx, self.make_pin(pin_data)).await?;
+		entry.author = Some(original.author.id.get());
+		entry.content = original.content.clone();
+
+		let Some(reaction) = original.reactions.iter().find(|r| r.me) else {
+			return entry;
+		};
+		entry.emoji = Some(EmojiType::from(&reaction.reaction_type));
+
+		let mut after: Option<UserId> = None;
+		loop {
+			let users = match original
+				.reaction_users(ctx, reaction.reaction_type.clone(), Some(100), after)
+				.await
+			{
+				Ok(users) => users,
+				Err(e) => {
+					logger::error_fmt!("Could not get reactors of {}: {}", message, e);
+					break;
+				}
+			};
+
+			let Some(last) = users.last() else {
+				break;
+			};
+			after = Some(last.id);
+
+			entry
+				.reactors
+				.extend(users.iter().filter(|u| !u.bot).map(|u| u.id.get()));
+		}
+
+		entry
+	}
+
+	/// Titles a hall post, leaving the rest of it and its gallery as they are
+	async fn mark_pin(
+		&self,
+		ctx: &Context,
+		channel: ChannelId,
+		pin_id: MessageId,
+		title: &str,
+	) -> GovanResult {
+		let mut pin = channel.message(ctx, pin_id).await?;
+
+		let mut embeds = pin
+			.embeds
+			.iter()
+			.cloned()
+			.map(CreateEmbed::from)
+			.collect::<Vec<_>>();
+		let Some(first) = embeds.first_mut() else {
+			return Ok(());
+		};
+		*first = first.clone().title(title);
+
+		pin.edit(ctx, EditMessage::new().embeds(embeds)).await?;
 
 		Ok(())
 	}
 
-	fn make_pin(&self, data: PinData) -> CreateMessage {
+	/// Brings along every video and audio file up to `limit` bytes, and links to the rest
+	async fn make_pin(&self, ctx: &Context, data: &PinData, limit: u32) -> CreateMessage {
+		let mut files = Vec::new();
+		for media in data.media.iter().filter(|m| m.size <= limit) {
+			match CreateAttachment::url(ctx, &media.url).await {
+				Ok(mut file) => {
+					file.filename = media.name.clone();
+					files.push(file);
+				}
+				Err(e) => logger::error_fmt!("Could not bring along {}: {}", media.name, e),
+			}
+		}
+
+		let uploaded = files.iter().map(|f| f.filename.clone()).collect::<Vec<_>>();
+
+		CreateMessage::default()
+			.embeds(self.make_pin_embeds(data, &uploaded))
+			.add_files(files)
+	}
+
+	/// One embed with the message, and one more per extra image. They share a URL so Discord
+	/// shows the images as a gallery
+	fn make_pin_embeds(&self, data: &PinData, uploaded: &[String]) -> Vec<CreateEmbed> {
 		let mut embed = CreateEmbed::default()
-			.color(data.r << 16 | data.g << 8 | data.b)
-			.author(CreateEmbedAuthor::new(data.author).icon_url(data.author_avatar))
+			.color(data.color)
+			.url(&data.message_url)
+			.author(CreateEmbedAuthor::new(&data.author).icon_url(&data.author_avatar))
 			.timestamp(data.timestamp)
-			.footer(
-				CreateEmbedFooter::new(format!("{} - {}", data.message_id, data.channel_id))
-					.icon_url(data.icon_url),
-			);
-
-		let teleport = match data.embed {
-			Embed::Image(url) => {
-				embed = embed.image(url);
-				format!("[Click to teleport]({})", data.message_url)
-			}
-			Embed::Video(name) => format!("[Click to go watch {}]({})", name, data.message_url),
-			Embed::Audio(name) => {
-				format!("[Click to go listen to {}]({})", name, data.message_url)
-			}
-			Embed::Nothing => format!("[Click to teleport]({})", data.message_url),
-		};
+			.footer(CreateEmbedFooter::new(data.footer()).icon_url(&data.icon_url));
+
+		let mut links = data
+			.media
+			.iter()
+			.filter(|m| !uploaded.contains(&m.name))
+			.map(|m| {
+				format!(
+					"[Click to go {} {}]({}) (too big to bring along)",
+					m.kind.verb(),
+					m.name,
+					data.message_url
+				)
+			})
+			.collect::<Vec<_>>();
 
-		if let Some(content) = data.content {
+		if links.is_empty() {
+			links.push(format!(
+				"[{}]({})",
+				data.fill(&data.style.teleport),
+				data.message_url
+			));
+		}
+
+		let teleport = links.join("\n");
+
+		if let Some(content) = &data.content {
 			embed = embed.description(content);
-			embed = embed.field("\u{200b}", teleport, false);
 		} else {
-			embed = embed.description(teleport);
+			embed = embed.description(&teleport);
+		}
+		if let Some(reply) = &data.reply {
+			embed = embed.field(reply.title(), reply.snippet(), false);
+		}
+		if let Some(reactions) = data.reactions() {
+			embed = embed.field("Reactions", reactions, false);
+		}
+		if data.content.is_some() {
+			embed = embed.field(TELEPORT_FIELD, teleport, false);
+		}
+
+		let mut images = data.images.iter();
+		if let Some(first) = images.next() {
+			embed = embed.image(first);
 		}
 
-		CreateMessage::default().add_embed(embed)
+		std::iter::once(embed)
+			.chain(images.map(|image| CreateEmbed::default().url(&data.message_url).image(image)))
+			.take(MAX_EMBEDS)
+			.collect()
 	}
 }
 
-enum Embed {
-	Image(String),
-	Video(String),
-	Audio(String),
-	Nothing,
+#[derive(Clone, Copy)]
+enum MediaKind {
+	Video,
+	Audio,
+}
+
+impl MediaKind {
+	fn verb(&self) -> &'static str {
+		match self {
+			MediaKind::Video => "watch",
+			MediaKind::Audio => "listen to",
+		}
+	}
+}
+
+struct Media {
+	kind: MediaKind,
+	name: String,
+	url: String,
+	size: u32,
+}
+
+struct Reply {
+	author: String,
+	content: String,
+	url: String,
+}
+
+impl Reply {
+	fn title(&self) -> String {
+		format!("Replying to {}", self.author)
+	}
+
+	fn snippet(&self) -> String {
+		let mut snippet = self
+			.content
+			.chars()
+			.take(REPLY_SNIPPET)
+			.map(|c| if c == '\n' { ' ' } else { c })
+			.collect::<String>();
+
+		if self.content.chars().count() > REPLY_SNIPPET {
+			snippet.push('…');
+		}
+
+		if snippet.is_empty() {
+			format!("[Click to see it]({})", self.url)
+		} else {
+			format!("> {}\n[Click to see it]({})", snippet, self.url)
+		}
+	}
 }
 
 struct PinData {
+	hall: String,
+	style: HallEmbed,
+	/// Names of who reacted
+	reactors: Vec<String>,
 	icon_url: String,
-	r: u32,
-	g: u32,
-	b: u32,
+	color: u32,
 	message_url: String,
 	author: String,
 	author_avatar: String,
@@ -143,5 +753,179 @@ struct PinData {
 	timestamp: Timestamp,
 	message_id: u64,
 	channel_id: u64,
-	embed: Embed,
+	images: Vec<String>,
+	media: Vec<Media>,
+	reply: Option<Reply>,
+}
+
+pub struct BackfillReport {
+	/// Entries found in the hall channels
+	pub entries: usize,
+	/// Entries whose original message is gone
+	pub deleted: Vec<HallEntry>,
+	/// Posts I have no entry of in channels shared by several halls, which were left out
+	pub unknown: usize,
+}
+
+/// Text of the original message as a hall post shows it, if it had any
+fn pin_content(pin: &Message) -> Option<String> {
+	let embed = pin.embeds.first()?;
+
+	// Without text the description is where the teleport link goes
+	embed
+		.fields
+		.iter()
+		.any(|field| field.name == TELEPORT_FIELD)
+		.then(|| embed.description.clone())
+		.flatten()
+}
+
+/// Biggest file a guild takes, boosts raise it
+fn upload_limit(ctx: &Context, guild_id: GuildId) -> u32 {
+	match ctx.cache.guild(guild_id).map(|guild| guild.premium_tier) {
+		Some(PremiumTier::Tier2) => 50 * 1024 * 1024,
+		Some(PremiumTier::Tier3) => 100 * 1024 * 1024,
+		_ => MAX_UPLOAD,
+	}
+}
+
+/// One of 4096 colours
+fn random_color() -> u32 {
+	let r = random::from_range(0..0x10) * 0x10;
+	let g = random::from_range(0..0x10) * 0x10;
+	let b = random::from_range(0..0x10) * 0x10;
+	r << 16 | g << 8 | b
+}
+
+async fn reactor_names(ctx: &Context, guild_id: GuildId, reactors: &[u64]) -> Vec<String> {
+	let names = util::member_names(ctx, Some(guild_id), reactors).await;
+	reactors.iter().map(|id| names[id].clone()).collect()
+}
+
+/// The message and channel a hall post came from, out of its footer
+fn pin_origin(pin: &Message) -> Option<(u64, u64)> {
+	let footer = pin.embeds.first()?.footer.as_ref()?;
+	let groups = FOOTER_PIN.captures(&footer.text)?;
+
+	Some((groups[1].parse().ok()?, groups[2].parse().ok()?))
+}
+
+impl PinData {
+	fn new(
+		msg: Message,
+		hall: &str,
+		style: HallEmbed,
+		icon_url: String,
+		color: u32,
+		reactors: Vec<String>,
+	) -> PinData {
+		let mut images = Vec::new();
+		let mut media = Vec::new();
+
+		for attachment in msg.attachments.iter() {
+			let content_type = attachment.content_type.as_deref().unwrap_or_default();
+			let kind = if content_type.starts_with("video/") {
+				MediaKind::Video
+			} else if content_type.starts_with("audio/") {
+				MediaKind::Audio
+			} else {
+				images.push(attachment.url.clone());
+				continue;
+			};
+
+			media.push(Media {
+				kind,
+				name: attachment.filename.clone(),
+				url: attachment.url.clone(),
+				size: attachment.size,
+			});
+		}
+
+		images.extend(msg.embeds.iter().filter_map(|embed| {
+			embed
+				.image
+				.as_ref()
+				.map(|image| image.url.clone())
+				.or_else(|| embed.thumbnail.as_ref().map(|thumb| thumb.url.clone()))
+		}));
+
+		images.extend(msg.sticker_items.iter().filter_map(|s| s.image_url()));
+
+		PinData {
+			hall: hall.to_string(),
+			style,
+			reactors,
+			icon_url,
+			color,
+			message_url: msg.link(),
+			author: msg.author.name.clone(),
+			author_avatar: msg
+				.author
+				.avatar_url()
+				.clone()
+				.unwrap_or(msg.author.default_avatar_url()),
+			reply: msg.referenced_message.as_ref().map(|reply| Reply {
+				author: reply.author.name.clone(),
+				content: reply.content.clone(),
+				url: reply.link(),
+			}),
+			content: msg.content.is_empty().not().then_some(msg.content),
+			timestamp: msg.timestamp,
+			message_id: msg.id.get(),
+			channel_id: msg.channel_id.get(),
+			images,
+			media,
+		}
+	}
+
+	/// A template from the hall's config with the placeholders filled in
+	fn fill(&self, template: &str) -> String {
+		template
+			.replace("{message}", &self.message_id.to_string())
+			.replace("{channel}", &self.channel_id.to_string())
+			.replace("{hall}", &self.hall)
+			.replace("{reactions}", &self.reactors.len().to_string())
+			.replace("{author}", &self.author)
+	}
+
+	fn footer(&self) -> String {
+		let footer = self.fill(&self.style.footer);
+
+		// Discord won't take an empty footer, and the icon needs one
+		if footer.is_empty() {
+			"\u{200b}".to_string()
+		} else {
+			footer
+		}
+	}
+
+	/// How many and who reacted, as much of it as the hall shows
+	fn reactions(&self) -> Option<String> {
+		let count = self
+			.style
+			.reaction_count
+			.then(|| self.reactors.len().to_string());
+
+		let names = (self.style.reactor_names && !self.reactors.is_empty()).then(|| {
+			let shown = self
+				.reactors
+				.iter()
+				.scan(0, |length, name| {
+					*length += name.len() + 2;
+					(*length <= REACTORS_LENGTH).then_some(name.as_str())
+				})
+				.collect::<Vec<_>>();
+
+			let mut names = shown.join(", ");
+			if shown.len() < self.reactors.len() {
+				names.push_str(&format!(" and {} more", self.reactors.len() - shown.len()));
+			}
+			names
+		});
+
+		match (count, names) {
+			(Some(count), Some(names)) => Some(format!("{}: {}", count, names)),
+			(count, names) => count.or(names),
+		}
+	}
 }
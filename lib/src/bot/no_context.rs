@@ -3,38 +3,76 @@ use crate::prelude::*;
 use serenity::builder::{CreateAttachment, CreateMessage, EditRole};
 use serenity::model::prelude::*;
 use serenity::prelude::*;
+use std::collections::HashSet;
 use std::ops::Deref;
 
 use crate::bot::Bot;
-use crate::data::Server;
+use crate::data::role_collections::RoleWin;
+use crate::data::{BotData, Server};
 
 impl Bot {
 	pub fn can_remove_context(&self, ctx: &Context, msg: &Message, server: &Server) -> bool {
 		server.no_context.as_ref().is_some_and(|nc| {
-			ctx.cache.guild(server.id).is_some_and(|g| {
-				nc.channel != 0
-					&& g.channels
-						.get(&ChannelId::new(nc.channel))
-						.is_some_and(|c| {
-							c.guild_id == server.id
-								&& c.permissions_for_user(ctx, ctx.cache.current_user().id)
-									.is_ok_and(|p| p.send_messages())
-						}) && nc.role != 0 && g.roles.contains_key(&RoleId::new(nc.role))
-			}) && msg.content.len() <= 280
+			let content = if msg.content.is_empty() {
+				(nc.attachments && !msg.attachments.is_empty())
+					|| (nc.stickers && !msg.sticker_items.is_empty())
+			} else {
+				(nc.min_length..=nc.max_length).contains(&msg.content.chars().count())
+			};
+
+			content
+				&& !nc.excluded_channels.contains(&msg.channel_id.get())
+				&& ctx.cache.guild(server.id).is_some_and(|g| {
+					nc.channel != 0
+						&& g.channels
+							.get(&ChannelId::new(nc.channel))
+							.is_some_and(|c| {
+								c.guild_id == server.id
+									&& c.permissions_for_user(ctx, ctx.cache.current_user().id)
+										.is_ok_and(|p| p.send_messages())
+							}) && nc.role != 0 && g.roles.contains_key(&RoleId::new(nc.role))
+				})
 		})
 	}
 
-	pub async fn remove_context(
-		&self,
-		ctx: &Context,
-		msg: &Message,
-		server: &Server,
-	) -> GovanResult {
+	/// Whether a message that can be picked gets picked, unless its author was picked too
+	/// recently. The odds get better the longer nobody was picked if the server has pity
+	pub fn pick_no_context(&self, msg: &Message, server: &Server, data: &BotData) -> bool {
+		let Some(nc) = server.no_context.as_ref() else {
+			return false;
+		};
+
+		let now = chrono::Utc::now().timestamp();
+		let collections = &data.role_collections;
+
+		if collections
+			.last_win(server.id, msg.author.id.get())
+			.is_some_and(|last| now - last < nc.cooldown as i64)
+		{
+			return false;
+		}
+
+		let odds = match (&nc.pity, collections.last_pick(server.id)) {
+			(Some(pity), Some(last)) => pity.odds(nc.odds, (now - last).max(0) as u64 / 60),
+			_ => nc.odds,
+		};
+
+		util::random::one_in(odds.max(1))
+	}
+
+	pub async fn remove_context(&self, ctx: &Context, msg: &Message) -> GovanResult {
+		let guild_id = msg.guild_id.unwrap_or_default();
 		let misconfigured_error = govanerror::error_lazy!(
-			log fmt = ("Server misconfigured: {}", server.id),
+			log fmt = ("Server misconfigured: {}", guild_id),
 			user = "< This guy's caretaker dun goof'd"
 		);
-		let no_context = server.no_context.as_ref().ok_or_else(misconfigured_error)?;
+		let no_context = self
+			.data()
+			.await
+			.servers
+			.get(&guild_id.get())
+			.and_then(|server| server.no_context.clone())
+			.ok_or_else(misconfigured_error)?;
 
 		let channel = ChannelId::new(no_context.channel)
 			.to_channel(&ctx)
@@ -68,28 +106,128 @@ impl Bot {
 
 		channel.send_message(&ctx, b).await?;
 
+		let holders = self
+			.no_context_holders(ctx, channel.guild_id, role.id)
+			.await;
+
+		let mut tracked = HashSet::from([msg.author.id.get()]);
+		for holder in holders.iter().filter(|h| **h != msg.author.id.get()) {
+			if let Err(e) = ctx
+				.http
+				.remove_member_role(channel.guild_id, UserId::new(*holder), role.id, None)
+				.await
+			{
+				// Unless they left or the role is gone, they're tried again next time
+				let gone = [util::UNKNOWN_MEMBER, util::UNKNOWN_ROLE];
+				if !util::is_discord_error(&e, &gone) {
+					logger::error_fmt!("Could not take the no-context role from {}: {}", holder, e);
+					tracked.insert(*holder);
+				}
+			}
+		}
+
+		let has_role = msg.member.as_ref().map_or_else(
+			|| holders.contains(&msg.author.id.get()),
+			|member| member.roles.contains(&role.id),
+		);
+		if !has_role {
+			ctx.http
+				.add_member_role(channel.guild_id, msg.author.id, role.id, None)
+				.await?;
+		}
+
+		self.no_context_holders
+			.lock()
+			.await
+			.insert(channel.guild_id.get(), tracked);
+
+		let new_role_name = self.data().await.random_no_context();
+		role.edit(&ctx, EditRole::default().name(&new_role_name))
+			.await?;
+
+		self.data.write().await.role_collections.record(RoleWin {
+			user: msg.author.id.get(),
+			guild: channel.guild_id.get(),
+			time: chrono::Utc::now().timestamp(),
+			name: new_role_name,
+		});
+
+		Ok(())
+	}
+
+	/// Everyone who might have the no-context role: whoever I know has it, whoever the cache
+	/// says has it, and whoever got it last
+	async fn no_context_holders(
+		&self,
+		ctx: &Context,
+		guild_id: GuildId,
+		role_id: RoleId,
+	) -> HashSet<u64> {
+		let mut holders = self
+			.no_context_holders
+			.lock()
+			.await
+			.get(&guild_id.get())
+			.cloned()
+			.unwrap_or_default();
+
+		if let Some(guild) = ctx.cache.guild(guild_id) {
+			holders.extend(
+				guild
+					.members
+					.values()
+					.filter(|member| member.roles.contains(&role_id))
+					.map(|member| member.user.id.get()),
+			);
+		}
+
+		if let Some(last) = self.data().await.role_collections.holder(guild_id.get()) {
+			holders.insert(last);
+		}
+
+		holders
+	}
+
+	/// Goes through every member of a guild to find who has the no-context role, for when
+	/// what I know might be wrong. Returns how many have it
+	pub async fn reconcile_no_context(
+		&self,
+		ctx: &Context,
+		guild_id: GuildId,
+	) -> GovanResult<usize> {
+		let role = self
+			.data()
+			.await
+			.servers
+			.get(&guild_id.get())
+			.and_then(|server| server.no_context.as_ref())
+			.filter(|no_context| no_context.role != 0)
+			.map(|no_context| RoleId::new(no_context.role))
+			.ok_or_else(govanerror::debug_lazy!(
+				log fmt = ("No no-context role to reconcile in {}", guild_id),
+				user = "This guild does not have a role to keep track of"
+			))?;
+
 		use serenity::futures::StreamExt;
 
-		let members = channel.guild_id.members_iter(ctx);
+		let members = guild_id.members_iter(ctx);
 		let members = members.collect::<Vec<_>>().await;
 
+		let mut holders = HashSet::new();
 		for member in members.into_iter() {
 			let member = member?;
 
-			let id = member.user.id;
-			if id == msg.author.id {
-				if !member.roles.contains(&role.id) {
-					member.add_role(&ctx, role.id).await?;
-				}
-			} else if member.roles.contains(&role.id) {
-				member.remove_role(&ctx, role.id).await?;
+			if member.roles.contains(&role) {
+				holders.insert(member.user.id.get());
 			}
 		}
 
-		let new_role_name = self.data().await.random_no_context();
-		role.edit(&ctx, EditRole::default().name(&new_role_name))
-			.await?;
+		let count = holders.len();
+		self.no_context_holders
+			.lock()
+			.await
+			.insert(guild_id.get(), holders);
 
-		Ok(())
+		Ok(count)
 	}
 }
@@ -63,6 +63,8 @@ impl Periodic {
 impl Bot {
 	pub async fn periodic_task(&self) {
 		self.pin_lock().await.cleanup(&self.http().await).await;
+		self.maybe_throwback().await;
+		self.maybe_digest().await;
 
 		if crate::util::random::one_in(3000) {
 			self.randomize_self().await;
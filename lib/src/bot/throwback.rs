@@ -0,0 +1,117 @@
+use super::Bot;
+use crate::data::Throwback;
+use crate::prelude::*;
+
+use chrono::{DateTime, Datelike, NaiveTime, Utc};
+use serenity::builder::{CreateEmbed, CreateMessage};
+use serenity::model::prelude::*;
+
+use crate::util::random;
+
+impl Bot {
+	/// Posts the throwbacks that are due, each guild gets one a day once it's past their time
+	pub async fn maybe_throwback(&self) {
+		let now = Utc::now();
+		let today = now.date_naive();
+
+		let due = {
+			let data = self.data().await;
+			data.servers
+				.values()
+				.filter_map(|server| {
+					let throwback = server.throwback.as_ref()?;
+					let time = NaiveTime::from_hms_opt(throwback.hour, throwback.minute, 0)?;
+
+					(now.time() >= time && data.schedule.last_throwback(server.id) != Some(today))
+						.then(|| (server.id, throwback.clone()))
+				})
+				.collect::<Vec<_>>()
+		};
+
+		for (guild, throwback) in due {
+			// If it couldn't be posted, it's tried again next time
+			if let Err(e) = self.throwback(guild, &throwback, now).await {
+				e.log();
+				continue;
+			}
+
+			let posted = self
+				.data
+				.write()
+				.await
+				.schedule
+				.throwback_posted(guild, today)
+				.await;
+			if let Err(e) = posted {
+				e.log();
+			}
+		}
+	}
+
+	/// Reposts a random hall entry sent on this day in an earlier year. Having none to repost
+	/// is fine, there's just nothing that day
+	async fn throwback(
+		&self,
+		guild: u64,
+		throwback: &Throwback,
+		now: DateTime<Utc>,
+	) -> GovanResult {
+		let sent_at = |message: u64| {
+			DateTime::from_timestamp(MessageId::new(message).created_at().unix_timestamp(), 0)
+		};
+
+		let mut candidates = self
+			.data()
+			.await
+			.hall_entries
+			.iter()
+			.filter(|e| e.guild == guild && e.hall == throwback.hall)
+			.filter(|e| !e.deleted && !e.retracted)
+			.filter(|e| {
+				sent_at(e.message).is_some_and(|sent| {
+					sent.year() < now.year()
+						&& sent.month() == now.month()
+						&& sent.day() == now.day()
+				})
+			})
+			.cloned()
+			.collect::<Vec<_>>();
+
+		let http = self.http().await;
+
+		// Originals and posts can go without me noticing, so keep trying until both are there
+		while !candidates.is_empty() {
+			let entry = candidates.swap_remove(random::from_range(0..candidates.len()));
+
+			let Ok(original) = ChannelId::new(entry.channel)
+				.message(&http, MessageId::new(entry.message))
+				.await
+			else {
+				continue;
+			};
+
+			let Ok(pin) = ChannelId::new(entry.hall_channel)
+				.message(&http, MessageId::new(entry.hall_message))
+				.await
+			else {
+				continue;
+			};
+
+			let year = sent_at(entry.message).map_or(now.year(), |sent| sent.year());
+
+			ChannelId::new(throwback.channel)
+				.send_message(
+					&http,
+					CreateMessage::default()
+						.content(format!("On this day in {}: {}", year, original.link()))
+						.embeds(pin.embeds.into_iter().map(CreateEmbed::from).collect()),
+				)
+				.await?;
+
+			return Ok(());
+		}
+
+		logger::debug_fmt!("No throwback in {} for {} today", throwback.hall, guild);
+		Ok(())
+	}
+}
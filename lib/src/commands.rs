@@ -1,6 +1,8 @@
 pub mod commander;
 
 mod color;
+mod hall;
+mod help;
 mod icon;
 mod ping;
 mod quit;
@@ -2,24 +2,31 @@ use crate::prelude::*;
 
 use crate::bot::Bot;
 use serenity::builder::EditRole;
-use serenity::model::prelude::*;
 use serenity::prelude::*;
 
-use super::commander::Arguments;
+use super::commander::Invocation;
 
 use sirgovan_macros::command;
 
 use rand::Rng;
 
-#[command(aliases = ["colour"])]
+#[command(
+	aliases = ["colour"],
+	slash,
+	description = "Change the color of your personal role",
+	usage = "[hex|random]",
+	cooldown = [user = 30]
+)]
 async fn color<'a>(
 	&self,
 	ctx: &Context,
-	msg: &'a Message,
-	mut words: Arguments<'a>,
+	msg: &'a Invocation<'a>,
 	_bot: &Bot,
+	#[description = "A hex color like #FF8800, or 'random'"]
+	#[suggest = ["random"]]
+	color: Option<&'a str>,
 ) -> GovanResult {
-	msg.guild_id.ok_or_else(govanerror::debug_lazy!(
+	msg.guild_id().ok_or_else(govanerror::debug_lazy!(
 		log = "Command used outside of guild",
 		user = "You need to be in a guild, silly!"
 	))?;
@@ -28,8 +35,6 @@ async fn color<'a>(
 
 	let mut top_role = member.get_unique_role(ctx)?;
 
-	let color = words.string();
-
 	match color {
 		None => {
 			msg.reply_report(
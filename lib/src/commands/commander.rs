@@ -1,10 +1,15 @@
 use crate::prelude::*;
+use crate::util::error::GovanError;
 
 use crate::bot::Bot;
-use crate::data::EmojiType;
+use crate::data::{Cooldown, EmojiType};
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use num_bigint::BigInt;
+use serenity::builder::{
+	CreateAutocompleteResponse, CreateCommand, CreateCommandOption, CreateInteractionResponse,
+};
 use serenity::model::prelude::*;
 use serenity::prelude::*;
 
@@ -25,17 +30,353 @@ pub trait Command: Send + Sync {
 		&[]
 	}
 
+	fn description() -> &'static str
+	where
+		Self: Sized,
+	{
+		""
+	}
+
+	fn usage() -> &'static str
+	where
+		Self: Sized,
+	{
+		Self::name()
+	}
+
+	fn slash() -> bool
+	where
+		Self: Sized,
+	{
+		false
+	}
+
+	fn options() -> &'static [SlashOption]
+	where
+		Self: Sized,
+	{
+		&[]
+	}
+
+	fn permission() -> Permission
+	where
+		Self: Sized,
+	{
+		Permission::Everyone
+	}
+
+	fn cooldown() -> Cooldown
+	where
+		Self: Sized,
+	{
+		Cooldown::default()
+	}
+
+	fn parent() -> Option<&'static str>
+	where
+		Self: Sized,
+	{
+		None
+	}
+
 	async fn execute<'a>(
 		&self,
 		ctx: &Context,
-		msg: &'a Message,
+		msg: &'a Invocation<'a>,
 		mut args: Arguments<'a>,
 		bot: &Bot,
 	) -> GovanResult;
 }
 
+/// Who is allowed to run a command. Owners can run everything, admins everything but owner commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Permission {
+	Everyone,
+	Admin,
+	Owner,
+}
+
+impl std::fmt::Display for Permission {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Permission::Everyone => write!(f, "everyone"),
+			Permission::Admin => write!(f, "admin"),
+			Permission::Owner => write!(f, "owner"),
+		}
+	}
+}
+
+impl Permission {
+	/// The highest permission the author of this invocation has
+	pub async fn of(ctx: &Context, msg: &Invocation<'_>, bot: &Bot) -> Permission {
+		let admin_roles = {
+			let data = bot.data().await;
+			if data.owners.contains(&msg.author().id.get()) {
+				return Permission::Owner;
+			}
+
+			match msg.guild_id().and_then(|id| data.servers.get(&id.get())) {
+				Some(server) if !server.admin_roles.is_empty() => server.admin_roles.clone(),
+				_ => return Permission::Everyone,
+			}
+		};
+
+		match msg.member(ctx).await {
+			Ok(member) if member.roles.iter().any(|r| admin_roles.contains(&r.get())) => {
+				Permission::Admin
+			}
+			_ => Permission::Everyone,
+		}
+	}
+}
+
+/// An argument a command can declare as a typed parameter
+pub trait FromArguments<'a>: Sized {
+	/// The slash command option type this argument is offered as
+	const KIND: CommandOptionType;
+
+	/// Consumes arguments only on success
+	fn from_arguments(args: &mut Arguments<'a>, ctx: &Context, msg: &Invocation) -> Option<Self>;
+}
+
+/// Optional arguments are only skipped when there's nothing left to parse
+impl<'a, T: FromArguments<'a>> FromArguments<'a> for Option<T> {
+	const KIND: CommandOptionType = T::KIND;
+
+	fn from_arguments(args: &mut Arguments<'a>, ctx: &Context, msg: &Invocation) -> Option<Self> {
+		if args.empty() {
+			Some(None)
+		} else {
+			T::from_arguments(args, ctx, msg).map(Some)
+		}
+	}
+}
+
+/// A single word
+impl<'a> FromArguments<'a> for &'a str {
+	const KIND: CommandOptionType = CommandOptionType::String;
+
+	fn from_arguments(args: &mut Arguments<'a>, _: &Context, _: &Invocation) -> Option<Self> {
+		args.string()
+	}
+}
+
+/// Everything that's left
+impl<'a> FromArguments<'a> for String {
+	const KIND: CommandOptionType = CommandOptionType::String;
+
+	fn from_arguments(args: &mut Arguments<'a>, _: &Context, _: &Invocation) -> Option<Self> {
+		if args.empty() {
+			None
+		} else {
+			Some(args.args.drain(..).collect::<Vec<_>>().join(" "))
+		}
+	}
+}
+
+impl<'a> FromArguments<'a> for Argument<'a> {
+	const KIND: CommandOptionType = CommandOptionType::String;
+
+	fn from_arguments(args: &mut Arguments<'a>, _: &Context, _: &Invocation) -> Option<Self> {
+		args.arg()
+	}
+}
+
+impl<'a> FromArguments<'a> for u64 {
+	const KIND: CommandOptionType = CommandOptionType::Integer;
+
+	fn from_arguments(args: &mut Arguments<'a>, _: &Context, _: &Invocation) -> Option<Self> {
+		args.number()
+	}
+}
+
+impl<'a> FromArguments<'a> for BigInt {
+	// Discord integers are too small for us
+	const KIND: CommandOptionType = CommandOptionType::String;
+
+	fn from_arguments(args: &mut Arguments<'a>, _: &Context, _: &Invocation) -> Option<Self> {
+		args.big_number()
+	}
+}
+
+impl<'a> FromArguments<'a> for EmojiType {
+	const KIND: CommandOptionType = CommandOptionType::String;
+
+	fn from_arguments(args: &mut Arguments<'a>, _: &Context, _: &Invocation) -> Option<Self> {
+		args.emoji()
+	}
+}
+
+impl<'a> FromArguments<'a> for Member {
+	const KIND: CommandOptionType = CommandOptionType::User;
+
+	fn from_arguments(args: &mut Arguments<'a>, ctx: &Context, msg: &Invocation) -> Option<Self> {
+		args.user(ctx, msg.guild_id()?.get())
+	}
+}
+
+/// A mention or an ID, whether or not they're cached
+impl<'a> FromArguments<'a> for UserId {
+	const KIND: CommandOptionType = CommandOptionType::User;
+
+	fn from_arguments(args: &mut Arguments<'a>, _: &Context, _: &Invocation) -> Option<Self> {
+		match args.try_arg()? {
+			Argument::User(id) | Argument::Number(id) if id != 0 => {
+				args.shift();
+				Some(UserId::new(id))
+			}
+			_ => None,
+		}
+	}
+}
+
+impl<'a> FromArguments<'a> for Role {
+	const KIND: CommandOptionType = CommandOptionType::Role;
+
+	fn from_arguments(args: &mut Arguments<'a>, ctx: &Context, msg: &Invocation) -> Option<Self> {
+		args.role(ctx, msg.guild_id()?.get())
+	}
+}
+
+impl<'a> FromArguments<'a> for GuildChannel {
+	const KIND: CommandOptionType = CommandOptionType::Channel;
+
+	fn from_arguments(args: &mut Arguments<'a>, ctx: &Context, msg: &Invocation) -> Option<Self> {
+		args.channel(ctx, msg.guild_id()?.get())
+	}
+}
+
+pub fn bad_argument(argument: &str, usage: &str, msg: &Invocation) -> GovanError {
+	govanerror::debug!(
+		log fmt = ("Could not parse argument {} of {}", argument, usage),
+		user fmt = ("That's not how it works. Usage: `{}{}`", msg.prefix(), usage)
+	)
+}
+
+pub struct SlashOption {
+	pub name: &'static str,
+	pub description: &'static str,
+	pub kind: CommandOptionType,
+	pub required: bool,
+	pub suggestions: &'static [&'static str],
+}
+
+impl SlashOption {
+	fn create(&self) -> CreateCommandOption {
+		CreateCommandOption::new(self.kind, self.name, self.description)
+			.required(self.required)
+			.set_autocomplete(!self.suggestions.is_empty())
+	}
+
+	fn token(&self, value: &CommandDataOptionValue) -> Option<String> {
+		match value {
+			CommandDataOptionValue::String(s) => Some(s.clone()),
+			CommandDataOptionValue::Integer(i) => Some(i.to_string()),
+			CommandDataOptionValue::Number(n) => Some(n.to_string()),
+			CommandDataOptionValue::Boolean(b) => Some(b.to_string()),
+			CommandDataOptionValue::User(id) => Some(format!("<@{}>", id)),
+			CommandDataOptionValue::Role(id) => Some(format!("<@&{}>", id)),
+			CommandDataOptionValue::Channel(id) => Some(format!("<#{}>", id)),
+			CommandDataOptionValue::Mentionable(id) => Some(format!("<@{}>", id)),
+			_ => None,
+		}
+	}
+}
+
+/// Everything a user might want to know about a command
+pub struct CommandInfo {
+	pub name: &'static str,
+	pub aliases: &'static [&'static str],
+	pub description: &'static str,
+	pub usage: &'static str,
+	pub options: &'static [SlashOption],
+	pub slash: bool,
+	pub permission: Permission,
+	pub cooldown: Cooldown,
+	/// Full name of the command this is a subcommand of
+	pub parent: Option<&'static str>,
+	/// Full name, including every parent command
+	pub path: String,
+}
+
+impl CommandInfo {
+	fn is_called(&self, name: &str) -> bool {
+		self.name == name || self.aliases.contains(&name)
+	}
+}
+
+/// What a cooldown applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum CooldownScope {
+	User(UserId),
+	Channel(ChannelId),
+	Guild(GuildId),
+}
+
+/// Where a command came from: a text message with the prefix of its server, or a slash command
+pub enum Invocation<'a> {
+	Message(&'a Message, &'a str),
+	Interaction(&'a CommandInteraction),
+}
+
+impl<'a> Invocation<'a> {
+	/// What goes before a command to use it here
+	pub fn prefix(&self) -> &str {
+		match self {
+			Invocation::Message(_, prefix) => prefix,
+			Invocation::Interaction(_) => "/",
+		}
+	}
+
+	pub fn author(&self) -> &User {
+		match self {
+			Invocation::Message(msg, _) => &msg.author,
+			Invocation::Interaction(interaction) => &interaction.user,
+		}
+	}
+
+	pub fn channel_id(&self) -> ChannelId {
+		match self {
+			Invocation::Message(msg, _) => msg.channel_id,
+			Invocation::Interaction(interaction) => interaction.channel_id,
+		}
+	}
+
+	pub fn guild_id(&self) -> Option<GuildId> {
+		match self {
+			Invocation::Message(msg, _) => msg.guild_id,
+			Invocation::Interaction(interaction) => interaction.guild_id,
+		}
+	}
+
+	pub async fn member(&self, ctx: &Context) -> GovanResult<Member> {
+		match self {
+			Invocation::Message(msg, _) => Ok(msg.member(ctx).await?),
+			Invocation::Interaction(interaction) => interaction
+				.member
+				.as_deref()
+				.cloned()
+				.ok_or_else(govanerror::debug_lazy!(
+					log = "Interaction has no member",
+					user = "You need to be in a guild, silly!"
+				)),
+		}
+	}
+
+	pub async fn reply_report(&self, ctx: &Context, content: impl std::fmt::Display + Send) {
+		match self {
+			Invocation::Message(msg, _) => msg.reply_report(ctx, content).await,
+			Invocation::Interaction(interaction) => interaction.followup_report(ctx, content).await,
+		}
+	}
+}
+
 pub struct Commander {
-	commands: HashMap<String, &'static dyn Command>,
+	commands: Vec<&'static dyn Command>,
+	infos: Vec<CommandInfo>,
+	names: HashMap<String, usize>,
+	slash_commands: HashMap<String, usize>,
+	cooldowns: Mutex<HashMap<(usize, CooldownScope), Instant>>,
 }
 
 impl Default for Commander {
@@ -47,7 +388,11 @@ impl Default for Commander {
 impl Commander {
 	pub fn new() -> Commander {
 		Commander {
-			commands: HashMap::new(),
+			commands: Vec::new(),
+			infos: Vec::new(),
+			names: HashMap::new(),
+			slash_commands: HashMap::new(),
+			cooldowns: Mutex::new(HashMap::new()),
 		}
 	}
 
@@ -55,28 +400,185 @@ impl Commander {
 		self.register_command(&super::color::Color);
 		self.register_command(&super::quit::Quit);
 		self.register_command(&super::role::Role);
+		self.register_command(&super::role::Collection);
+		self.register_command(&super::role::Leaderboard);
+		self.register_command(&super::role::Reconcile);
 		self.register_command(&super::icon::Icon);
 		self.register_command(&super::roll::Roll);
+		self.register_command(&super::roll::Stats);
+		self.register_command(&super::roll::Leaderboard);
+		self.register_command(&super::hall::Hall);
+		self.register_command(&super::hall::Stats);
+		self.register_command(&super::hall::Search);
+		self.register_command(&super::hall::Backfill);
 		self.register_command(&super::ping::Ping);
 		self.register_command(&super::refresh::Refresh);
+		self.register_command(&super::refresh::All);
+		self.register_command(&super::refresh::Servers);
+		self.register_command(&super::refresh::Roles);
+		self.register_command(&super::refresh::Strings);
+		self.register_command(&super::help::Help);
 	}
 
+	/// Subcommands must be registered after their parent
 	pub fn register_command<T: Command + 'static>(&mut self, command: &'static T) {
-		self.commands.insert(format!("!{}", T::name()), command);
+		let path = match T::parent() {
+			Some(parent) => {
+				assert!(
+					self.infos.iter().any(|info| info.path == parent),
+					"Subcommand {} registered before its parent {}",
+					T::name(),
+					parent
+				);
+				format!("{} {}", parent, T::name())
+			}
+			None => T::name().to_string(),
+		};
+
+		let index = self.infos.len();
+		self.commands.push(command);
+		self.infos.push(CommandInfo {
+			name: T::name(),
+			aliases: T::aliases(),
+			description: T::description(),
+			usage: T::usage(),
+			options: T::options(),
+			slash: T::slash(),
+			permission: T::permission(),
+			cooldown: T::cooldown(),
+			parent: T::parent(),
+			path,
+		});
+
+		// Subcommands are found through their parent
+		if T::parent().is_some() {
+			return;
+		}
+
+		self.names.insert(T::name().to_string(), index);
 		for alias in T::aliases().iter() {
-			self.commands.insert(format!("!{}", alias), command);
+			self.names.insert(alias.to_string(), index);
+		}
+
+		if T::slash() {
+			self.slash_commands.insert(T::name().to_string(), index);
+		}
+	}
+
+	/// Every command that is not a subcommand
+	pub fn infos(&self) -> impl Iterator<Item = &CommandInfo> {
+		self.infos.iter().filter(|info| info.parent.is_none())
+	}
+
+	pub fn subcommands<'a>(
+		&'a self,
+		info: &'a CommandInfo,
+	) -> impl Iterator<Item = &'a CommandInfo> {
+		self.infos
+			.iter()
+			.filter(|sub| sub.parent == Some(info.path.as_str()))
+	}
+
+	fn subcommand(&self, index: usize, name: &str) -> Option<usize> {
+		let path = self.infos[index].path.as_str();
+		self.infos
+			.iter()
+			.position(|info| info.parent == Some(path) && info.is_called(name))
+	}
+
+	/// Finds a command by its full name, like "refresh strings"
+	pub fn info(&self, name: &str) -> Option<&CommandInfo> {
+		let mut words = name.split_whitespace();
+		let first = words.next()?.trim_start_matches(['!', '/']);
+
+		let mut index = self
+			.infos
+			.iter()
+			.position(|info| info.parent.is_none() && info.is_called(first))?;
+		for word in words {
+			index = self.subcommand(index, word)?;
+		}
+
+		Some(&self.infos[index])
+	}
+
+	/// An error listing every subcommand of a command, for when none of them was used
+	pub fn bad_subcommand(&self, name: &str, msg: &Invocation) -> GovanError {
+		let Some(info) = self.info(name) else {
+			return govanerror::error!(log fmt = ("No command {} to list subcommands of", name));
+		};
+
+		let subcommands = self
+			.subcommands(info)
+			.map(|sub| sub.name)
+			.collect::<Vec<_>>();
+
+		govanerror::debug!(
+			log fmt = ("No subcommand of {} given", info.path),
+			user fmt = ("That's not how it works. Usage: `{}{} <{}>`", msg.prefix(), info.path, subcommands.join("|"))
+		)
+	}
+
+	pub fn slash_commands(&self) -> Vec<CreateCommand> {
+		self.slash_commands
+			.iter()
+			.map(|(name, index)| {
+				let info = &self.infos[*index];
+				let subcommands = self
+					.subcommands(info)
+					.filter(|sub| sub.slash)
+					.map(|sub| {
+						CreateCommandOption::new(
+							CommandOptionType::SubCommand,
+							sub.name,
+							sub.description,
+						)
+						.set_sub_options(sub.options.iter().map(SlashOption::create))
+					})
+					.collect::<Vec<_>>();
+
+				// Discord doesn't allow mixing subcommands and options
+				let options = if subcommands.is_empty() {
+					info.options.iter().map(SlashOption::create).collect()
+				} else {
+					subcommands
+				};
+
+				CreateCommand::new(name)
+					.description(info.description)
+					.set_options(options)
+			})
+			.collect()
+	}
+
+	/// What's after the prefix of this server, or after a mention of me
+	fn strip_prefix<'a>(ctx: &Context, content: &'a str, prefix: &str) -> Option<&'a str> {
+		if let Some(rest) = content.strip_prefix(prefix) {
+			return (!rest.starts_with(char::is_whitespace)).then_some(rest);
 		}
+
+		let me = ctx.cache.current_user().id;
+		[format!("<@{}>", me), format!("<@!{}>", me)]
+			.iter()
+			.find_map(|mention| content.strip_prefix(mention.as_str()))
+			.map(str::trim_start)
 	}
 
 	pub async fn parse(&self, ctx: &Context, msg: &Message, bot: &Bot) -> GovanResult {
-		if !msg.content.starts_with('!') {
+		let prefix = bot
+			.data()
+			.await
+			.prefix(msg.guild_id.map(GuildId::get))
+			.to_string();
+
+		let Some(content) = Commander::strip_prefix(ctx, &msg.content, &prefix) else {
 			return Err(govanerror::debug!(
 				// log = "No such command"
 			));
-		}
+		};
 
-		let content = msg.content.clone();
-		let mut words = Arguments::from(content.as_str());
+		let tokens = Tokens::from(content);
+		let mut words = tokens.arguments();
 
 		if words.empty() {
 			return Err(govanerror::debug!(
@@ -88,15 +590,201 @@ impl Commander {
 			.string()
 			.expect("Non-empty arguments didn't return string");
 
-		if let Some(c) = self.commands.get(first) {
-			Ok(c.execute(ctx, msg, words, bot).await?)
-		} else {
-			Err(govanerror::debug!(log = "No such command"))
+		let Some(mut index) = self.names.get(first).copied() else {
+			return Err(govanerror::debug!(log = "No such command"));
+		};
+
+		while let Some(sub) = words.peek().and_then(|word| self.subcommand(index, word)) {
+			words.shift();
+			index = sub;
+		}
+
+		self.dispatch(ctx, index, &Invocation::Message(msg, &prefix), words, bot)
+			.await
+	}
+
+	async fn dispatch<'a>(
+		&self,
+		ctx: &Context,
+		index: usize,
+		msg: &'a Invocation<'a>,
+		args: Arguments<'a>,
+		bot: &Bot,
+	) -> GovanResult {
+		let info = &self.infos[index];
+
+		// A subcommand can't be more permissive than its parents
+		let mut permission = info.permission;
+		let mut parent = info.parent;
+		while let Some(path) = parent {
+			let Some(p) = self.infos.iter().find(|i| i.path == path) else {
+				break;
+			};
+			permission = permission.max(p.permission);
+			parent = p.parent;
+		}
+
+		if Permission::of(ctx, msg, bot).await < permission {
+			return Err(govanerror::error!(
+				log fmt = ("Attempted illegal !{} by non-{}: {}", info.path, permission, msg.author().name),
+				user = "Nuh-uh"
+			));
+		}
+
+		self.cool_down(index, msg, bot).await?;
+
+		self.commands[index].execute(ctx, msg, args, bot).await
+	}
+
+	/// Fails if the command is still cooling down in any scope, otherwise starts all its cooldowns
+	async fn cool_down(&self, index: usize, msg: &Invocation<'_>, bot: &Bot) -> GovanResult {
+		let info = &self.infos[index];
+		let cooldown = {
+			let data = bot.data().await;
+			msg.guild_id()
+				.and_then(|id| data.servers.get(&id.get()))
+				.and_then(|server| server.cooldowns.get(&info.path))
+				.map_or(info.cooldown, |c| c.or(info.cooldown))
+		};
+
+		let scopes = [
+			(cooldown.user, Some(CooldownScope::User(msg.author().id))),
+			(
+				cooldown.channel,
+				Some(CooldownScope::Channel(msg.channel_id())),
+			),
+			(cooldown.guild, msg.guild_id().map(CooldownScope::Guild)),
+		]
+		.into_iter()
+		.filter_map(|(seconds, scope)| match (seconds, scope) {
+			(Some(seconds), Some(scope)) if seconds > 0 => {
+				Some((Duration::from_secs(seconds), scope))
+			}
+			_ => None,
+		})
+		.collect::<Vec<_>>();
+
+		if scopes.is_empty() {
+			return Ok(());
+		}
+
+		let now = Instant::now();
+		let mut cooldowns = self.cooldowns.lock().await;
+		cooldowns.retain(|_, until| *until > now);
+
+		if let Some(until) = scopes
+			.iter()
+			.filter_map(|(_, scope)| cooldowns.get(&(index, *scope)))
+			.max()
+		{
+			let wait = (*until - now).as_secs_f64().ceil();
+			return Err(govanerror::debug!(
+				log fmt = ("!{} is cooling down for {}", info.path, msg.author().name),
+				user fmt = ("Slow down! You can use `{}{}` again in {}s", msg.prefix(), info.path, wait)
+			));
+		}
+
+		for (duration, scope) in scopes {
+			cooldowns.insert((index, scope), now + duration);
 		}
+
+		Ok(())
+	}
+
+	/// The slash command or subcommand that was used, and the options given to it
+	fn slash_command<'a>(
+		&self,
+		interaction: &'a CommandInteraction,
+	) -> Option<(usize, &'a [CommandDataOption])> {
+		let index = *self.slash_commands.get(&interaction.data.name)?;
+
+		match interaction.data.options.first() {
+			Some(CommandDataOption {
+				name,
+				value: CommandDataOptionValue::SubCommand(options),
+				..
+			}) => Some((self.subcommand(index, name)?, options)),
+			_ => Some((index, &interaction.data.options)),
+		}
+	}
+
+	pub async fn interact(
+		&self,
+		ctx: &Context,
+		interaction: &CommandInteraction,
+		bot: &Bot,
+	) -> GovanResult {
+		let (index, options) =
+			self.slash_command(interaction)
+				.ok_or_else(govanerror::error_lazy!(
+					log fmt = ("No such slash command: {}", interaction.data.name),
+					user = "I don't know that one anymore"
+				))?;
+
+		// Options come in whatever order the user filled them in, commands expect them in order
+		let tokens = self.infos[index]
+			.options
+			.iter()
+			.filter_map(|option| {
+				options
+					.iter()
+					.find(|o| o.name == option.name)
+					.and_then(|o| option.token(&o.value))
+			})
+			.collect::<Vec<_>>();
+
+		let words = tokens.iter().map(String::as_str).collect::<Arguments>();
+
+		self.dispatch(
+			ctx,
+			index,
+			&Invocation::Interaction(interaction),
+			words,
+			bot,
+		)
+		.await
+	}
+
+	pub async fn autocomplete(
+		&self,
+		ctx: &Context,
+		interaction: &CommandInteraction,
+	) -> GovanResult {
+		let (index, _) = self
+			.slash_command(interaction)
+			.ok_or_else(govanerror::debug_lazy!(
+				log fmt = ("No such slash command to autocomplete: {}", interaction.data.name)
+			))?;
+
+		let focused = interaction
+			.data
+			.autocomplete()
+			.ok_or_else(govanerror::debug_lazy!(log = "Nothing to autocomplete"))?;
+
+		let suggestions = self.infos[index]
+			.options
+			.iter()
+			.find(|o| o.name == focused.name)
+			.map_or(&[][..], |o| o.suggestions);
+
+		let typed = focused.value.to_lowercase();
+		let response = suggestions
+			.iter()
+			.filter(|s| s.to_lowercase().starts_with(&typed))
+			.fold(CreateAutocompleteResponse::new(), |response, s| {
+				response.add_string_choice(*s, *s)
+			});
+
+		interaction
+			.create_response(ctx, CreateInteractionResponse::Autocomplete(response))
+			.await?;
+
+		Ok(())
 	}
 }
 
 #[allow(dead_code)]
+#[derive(Debug, PartialEq)]
 pub enum Argument<'a> {
 	String(&'a str),
 	Number(u64),
@@ -107,14 +795,107 @@ pub enum Argument<'a> {
 	Emoji(EmojiType),
 }
 
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+	Word(String),
+	Named(String, String),
+	Flag(String),
+}
+
+/// A command line split into words. Quotes group words together, `\"` and `\\` are escapes,
+/// `key=value` and `--flag` are named arguments unless quoted
+pub struct Tokens {
+	tokens: Vec<Token>,
+}
+
+impl From<&str> for Tokens {
+	fn from(value: &str) -> Tokens {
+		let mut tokens = vec![];
+		let mut chars = value.chars().peekable();
+
+		loop {
+			while chars.next_if(|c| c.is_whitespace()).is_some() {}
+			if chars.peek().is_none() {
+				break;
+			}
+
+			let mut text = String::new();
+			let mut key = None;
+			let mut quoted = false;
+			let mut was_quoted = false;
+
+			while let Some(c) = chars.next() {
+				match c {
+					'\\' if chars.peek().is_some_and(|&n| n == '"' || n == '\\') => {
+						text.extend(chars.next());
+					}
+					'"' => {
+						quoted = !quoted;
+						was_quoted = true;
+					}
+					c if c.is_whitespace() && !quoted => break,
+					'=' if !quoted && !was_quoted && key.is_none() && Tokens::is_name(&text) => {
+						key = Some(std::mem::take(&mut text));
+					}
+					c => text.push(c),
+				}
+			}
+
+			tokens.push(match key {
+				Some(key) => match key.strip_prefix("--") {
+					Some(flag) => Token::Named(flag.to_string(), text),
+					None => Token::Named(key, text),
+				},
+				None => match text.strip_prefix("--") {
+					Some(flag) if !was_quoted && Tokens::is_name(flag) => {
+						Token::Flag(flag.to_string())
+					}
+					_ => Token::Word(text),
+				},
+			});
+		}
+
+		Tokens { tokens }
+	}
+}
+
+impl Tokens {
+	fn is_name(text: &str) -> bool {
+		!text.is_empty()
+			&& text
+				.chars()
+				.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+	}
+
+	pub fn arguments(&self) -> Arguments<'_> {
+		let mut arguments = Arguments::default();
+
+		for token in self.tokens.iter() {
+			match token {
+				Token::Word(word) => arguments.args.push_back(word),
+				Token::Named(key, value) => {
+					arguments.named.insert(key, value);
+				}
+				Token::Flag(flag) => arguments.flags.push(flag),
+			}
+		}
+
+		arguments
+	}
+}
+
+#[derive(Default)]
 pub struct Arguments<'a> {
 	args: VecDeque<&'a str>,
+	named: HashMap<&'a str, &'a str>,
+	flags: Vec<&'a str>,
 }
 
-impl<'a, 'b: 'a> From<&'b str> for Arguments<'a> {
-	fn from(value: &'b str) -> Arguments<'a> {
+impl<'a> FromIterator<&'a str> for Arguments<'a> {
+	fn from_iter<T: IntoIterator<Item = &'a str>>(iter: T) -> Self {
 		Arguments {
-			args: value[..].split_whitespace().collect::<VecDeque<_>>(),
+			args: iter.into_iter().collect(),
+			..Default::default()
 		}
 	}
 }
@@ -168,7 +949,8 @@ impl<'a> Arguments<'a> {
 							}
 							Some('>') => Some(Argument::String(arg)),
 							Some(_) => {
-								let maybe_id = &arg[2..arg.len() - 1];
+								// Nickname mentions have a ! before the id
+								let maybe_id = arg[2..arg.len() - 1].trim_start_matches('!');
 								match maybe_id.parse::<u64>() {
 									Ok(num) => Some(Argument::User(num)),
 									Err(_) => Some(Argument::String(arg)),
@@ -177,7 +959,10 @@ impl<'a> Arguments<'a> {
 							None => unreachable!(), // We have to find a >
 						}
 					}
-					Some(':') => {
+					// Animated emoji start with an a
+					Some(':') | Some('a')
+						if arg[1..].starts_with(':') || arg[1..].starts_with("a:") =>
+					{
 						if let Some(pos) = arg.rfind(':') {
 							let maybe_id = &arg[pos + 1..arg.len() - 1];
 							match maybe_id.parse::<u64>() {
@@ -216,6 +1001,21 @@ impl<'a> Arguments<'a> {
 		self.args.pop_front()
 	}
 
+	pub fn peek(&self) -> Option<&'a str> {
+		self.args.front().copied()
+	}
+
+	/// Takes the value given as `key=value` or `--key=value`
+	pub fn named(&mut self, key: &str) -> Option<&'a str> {
+		self.named.remove(key)
+	}
+
+	/// Whether `--flag` was given, only true the first time it's asked
+	pub fn flag(&mut self, flag: &str) -> bool {
+		let position = self.flags.iter().position(|f| *f == flag);
+		position.map(|p| self.flags.remove(p)).is_some()
+	}
+
 	pub fn rest(mut self) -> String {
 		self.args.make_contiguous().join(" ")
 	}
@@ -271,11 +1071,7 @@ impl<'a> Arguments<'a> {
 		}
 	}
 
-	pub fn user<'b>(
-		&'a mut self,
-		ctx: &'b Context,
-		guild_id: u64,
-	) -> Option<serenity::all::Member> {
+	pub fn user(&mut self, ctx: &Context, guild_id: u64) -> Option<serenity::all::Member> {
 		let user = self.user_id()?;
 		let guild = &ctx.cache.guild(guild_id)?;
 		guild.members.get(&UserId::new(user)).cloned()
@@ -290,7 +1086,7 @@ impl<'a> Arguments<'a> {
 		}
 	}
 
-	pub fn role<'b>(&'a mut self, ctx: &'b Context, guild_id: u64) -> Option<serenity::all::Role> {
+	pub fn role(&mut self, ctx: &Context, guild_id: u64) -> Option<serenity::all::Role> {
 		let role = self.role_id()?;
 		let guild = ctx.cache.guild(guild_id)?;
 		guild.roles.get(&RoleId::new(role)).cloned()
@@ -314,3 +1110,59 @@ impl<'a> Arguments<'a> {
 			.and_then(|g| g.emojis.get(&emoji.into()).cloned())
 	}
 }
+
+#[test]
+fn arguments_split_quotes_and_escapes() {
+	let tokens = Tokens::from(r#"!say  "two words" one \"quoted\" "say \"hi\"" a\b """#);
+	let mut args = tokens.arguments();
+
+	assert_eq!(args.string(), Some("!say"));
+	assert_eq!(args.string(), Some("two words"));
+	assert_eq!(args.string(), Some("one"));
+	assert_eq!(args.string(), Some("\"quoted\""));
+	assert_eq!(args.string(), Some("say \"hi\""));
+	assert_eq!(args.string(), Some("a\\b"));
+	assert_eq!(args.string(), Some(""));
+	assert!(args.empty());
+}
+
+#[test]
+fn arguments_named_and_flags() {
+	let tokens = Tokens::from(r#"search title="two words" --all --hall=fame "--not" "a=b" 3"#);
+	let mut args = tokens.arguments();
+
+	assert_eq!(args.named("title"), Some("two words"));
+	assert_eq!(args.named("hall"), Some("fame"));
+	assert_eq!(args.named("title"), None);
+	assert!(args.flag("all"));
+	assert!(!args.flag("all"));
+	assert!(!args.flag("not"));
+
+	assert_eq!(args.string(), Some("search"));
+	assert_eq!(args.string(), Some("--not"));
+	assert_eq!(args.string(), Some("a=b"));
+	assert_eq!(args.number(), Some(3));
+	assert!(args.empty());
+}
+
+#[test]
+fn arguments_mentions() {
+	let tokens = Tokens::from(
+		"<@1> <@!2> <@&3> <#4> <:govan:5> <a:govan:6> 🦀 https://a.com/?b=c -7 <@> <#x>",
+	);
+	let mut args = tokens.arguments();
+
+	assert_eq!(args.user_id(), Some(1));
+	assert_eq!(args.user_id(), Some(2));
+	assert_eq!(args.role_id(), Some(3));
+	assert_eq!(args.channel_id(), Some(4));
+	assert_eq!(args.emoji(), Some(EmojiType::Discord(5)));
+	assert_eq!(args.emoji(), Some(EmojiType::Discord(6)));
+	assert_eq!(args.emoji(), Some(EmojiType::Unicode("🦀".to_string())));
+	assert_eq!(args.user_id(), None);
+	assert_eq!(args.string(), Some("https://a.com/?b=c"));
+	assert_eq!(args.big_number(), Some(BigInt::from(-7)));
+	assert_eq!(args.arg(), Some(Argument::String("<@>")));
+	assert_eq!(args.arg(), Some(Argument::String("<#x>")));
+	assert!(args.empty());
+}
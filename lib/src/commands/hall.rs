@@ -0,0 +1,387 @@
+use crate::data::halls::{words, HallEntry, HallFilter, HallQuery};
+use crate::prelude::*;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use super::commander::{bad_argument, Arguments, Command, FromArguments, Invocation};
+use crate::bot::Bot;
+
+use sirgovan_macros::command;
+
+const TOP_SIZE: usize = 5;
+const MONTHS_SHOWN: usize = 12;
+const SEARCH_PAGE_SIZE: usize = 5;
+const SEARCH_SNIPPET: usize = 80;
+const SEARCH_KEYS: [&str; 5] = ["author", "hall", "before", "after", "page"];
+
+#[command(description = "Things about the halls of this server")]
+async fn hall<'a>(&self, _ctx: &Context, msg: &'a Invocation<'a>, bot: &Bot) -> GovanResult {
+	Err(bot.commander.bad_subcommand("hall", msg))
+}
+
+#[command(
+	parent = "hall",
+	aliases = ["top"],
+	description = "Who gets into the halls the most, in one hall, of one person or over a period like week, month, year or 30d",
+	usage = "[hall] [@user] [period]"
+)]
+async fn stats<'a>(
+	&self,
+	ctx: &Context,
+	msg: &'a Invocation<'a>,
+	bot: &Bot,
+	mut args: Arguments<'a>,
+) -> GovanResult {
+	let guild_id = msg.guild_id().ok_or_else(govanerror::debug_lazy!(
+		log = "Hall stats outside of a guild",
+		user = "You need to be in a guild, silly!"
+	))?;
+
+	let (filter, period, user) = {
+		let data = bot.data().await;
+		let server = data.servers.get(&guild_id.get());
+
+		let mut filter = HallFilter::default();
+		let mut period = None;
+		let mut user = None;
+
+		// Any order goes, they can't be mistaken for each other
+		while let Some(word) = args.peek() {
+			if let Some(hall) = server.and_then(|server| server.hall(word)) {
+				filter.hall = Some(hall.name.clone());
+				args.string();
+			} else if let Some(since) = since(word) {
+				filter.since = since;
+				period = Some(word.to_string());
+				args.string();
+			} else if let Some(id) = UserId::from_arguments(&mut args, ctx, msg) {
+				filter.user = Some(id.get());
+				user = Some(id);
+			} else {
+				return Err(bad_argument(word, <Self as Command>::usage(), msg));
+			}
+		}
+
+		(filter, period, user)
+	};
+
+	let stats = bot.data().await.hall_entries.stats(guild_id.get(), &filter);
+
+	let mut title = vec![filter
+		.hall
+		.clone()
+		.unwrap_or_else(|| "all halls".to_string())];
+	if let Some(user) = user {
+		title.push(util::member_name(ctx, Some(guild_id), user).await);
+	}
+	if let Some(period) = period {
+		title.push(period);
+	}
+
+	if stats.entries == 0 && stats.reacted == 0 {
+		msg.reply_report(ctx, format!("Nothing in {} yet", title.join(", ")))
+			.await;
+		return Ok(());
+	}
+
+	let mut lines = vec![format!(
+		"**Hall stats** ({}): {} entries",
+		title.join(", "),
+		stats.entries
+	)];
+
+	let users = stats
+		.authors
+		.iter()
+		.take(TOP_SIZE)
+		.chain(stats.reactors.iter().take(TOP_SIZE))
+		.map(|(id, _)| *id)
+		.collect::<Vec<_>>();
+	let names = util::member_names(ctx, Some(guild_id), &users).await;
+	let user = |id: u64| names[&id].clone();
+
+	if filter.user.is_some() {
+		lines.push(format!("Helped others in {} times", stats.reacted));
+	} else {
+		top(&mut lines, "Top authors", &stats.authors, user);
+	}
+	top(&mut lines, "Top reactors", &stats.reactors, user);
+	top(&mut lines, "Most pinned channels", &stats.channels, |id| {
+		format!("<#{}>", id)
+	});
+
+	if !stats.months.is_empty() {
+		lines.push("**Per month**".to_string());
+		let skip = stats.months.len().saturating_sub(MONTHS_SHOWN);
+		lines.extend(
+			stats
+				.months
+				.iter()
+				.skip(skip)
+				.map(|(month, count)| format!("{}: {}", month, count)),
+		);
+	}
+
+	msg.reply_report(ctx, lines.join("\n")).await;
+
+	Ok(())
+}
+
+#[command(
+	parent = "hall",
+	aliases = ["find"],
+	description = "Find hall entries by their text, dates go like 2023-09-30",
+	usage = "<text> [author:@user] [hall:name] [before:date] [after:date] [page:n]"
+)]
+async fn search<'a>(
+	&self,
+	ctx: &Context,
+	msg: &'a Invocation<'a>,
+	bot: &Bot,
+	mut args: Arguments<'a>,
+) -> GovanResult {
+	let guild_id = msg.guild_id().ok_or_else(govanerror::debug_lazy!(
+		log = "Hall search outside of a guild",
+		user = "You need to be in a guild, silly!"
+	))?;
+
+	// Both author:x and author=x work
+	let mut options = SEARCH_KEYS
+		.iter()
+		.filter_map(|key| Some((*key, args.named(key)?)))
+		.collect::<Vec<_>>();
+	let mut text = Vec::new();
+	while let Some(word) = args.string() {
+		match word.split_once(':') {
+			Some((key, value)) if SEARCH_KEYS.contains(&key) => options.push((key, value)),
+			_ => text.push(word),
+		}
+	}
+
+	let mut query = HallQuery {
+		text: text.join(" "),
+		..Default::default()
+	};
+	let mut page = 1;
+
+	for (key, value) in options {
+		let parsed = match key {
+			"author" => mention(value).map(|id| query.author = Some(id)),
+			"hall" => {
+				query.hall = Some(value.to_string());
+				Some(())
+			}
+			"before" => date(value).map(|time| query.before = Some(time)),
+			"after" => date(value).map(|time| query.after = Some(time)),
+			"page" => value
+				.parse::<usize>()
+				.ok()
+				.filter(|page| *page > 0)
+				.map(|p| page = p),
+			_ => None,
+		};
+
+		if parsed.is_none() {
+			return Err(bad_argument(key, <Self as Command>::usage(), msg));
+		}
+	}
+
+	// Punctuation alone isn't something to search for
+	if words(&query.text).next().is_none() {
+		return Err(bad_argument("text", <Self as Command>::usage(), msg));
+	}
+
+	let results = bot
+		.data()
+		.await
+		.hall_entries
+		.search(guild_id.get(), &query)
+		.into_iter()
+		.cloned()
+		.collect::<Vec<_>>();
+
+	if results.is_empty() {
+		msg.reply_report(ctx, "Nothing in the halls says that")
+			.await;
+		return Ok(());
+	}
+
+	let pages = results.len().div_ceil(SEARCH_PAGE_SIZE);
+	if page > pages {
+		msg.reply_report(ctx, format!("There's only {} pages", pages))
+			.await;
+		return Ok(());
+	}
+
+	let shown = results
+		.iter()
+		.enumerate()
+		.skip((page - 1) * SEARCH_PAGE_SIZE)
+		.take(SEARCH_PAGE_SIZE)
+		.collect::<Vec<_>>();
+	let authors = shown
+		.iter()
+		.filter_map(|(_, entry)| entry.author)
+		.collect::<Vec<_>>();
+	let names = util::member_names(ctx, Some(guild_id), &authors).await;
+
+	let mut lines = vec![format!(
+		"**{} found** (page {}/{})",
+		results.len(),
+		page,
+		pages
+	)];
+	lines.extend(shown.into_iter().map(|(i, entry)| {
+		let author = entry
+			.author
+			.map_or(util::UNKNOWN_USER, |author| names[&author].as_str());
+		result(i, entry, author)
+	}));
+	if page < pages {
+		lines.push(format!("Add `page:{}` for more", page + 1));
+	}
+
+	msg.reply_report(ctx, lines.join("\n")).await;
+
+	Ok(())
+}
+
+#[command(
+	parent = "hall",
+	description = "Record every post in this server's hall channels that I don't know about yet",
+	permission = "owner"
+)]
+async fn backfill<'a>(&self, ctx: &Context, msg: &'a Invocation<'a>, bot: &Bot) -> GovanResult {
+	let guild_id = msg.guild_id().ok_or_else(govanerror::debug_lazy!(
+		log = "Hall backfill outside of a guild",
+		user = "You need to be in a guild, silly!"
+	))?;
+
+	msg.reply_report(ctx, "Going through the halls, this might take a while")
+		.await;
+
+	let report = bot.backfill_halls(ctx, guild_id.get(), false).await?;
+
+	let mut reply = format!(
+		"Found {} new entries, {} of them with their original deleted",
+		report.entries,
+		report.deleted.len()
+	);
+	if report.unknown > 0 {
+		reply.push_str(&format!(
+			"\n{} posts were left out, their channel has more than one hall",
+			report.unknown
+		));
+	}
+
+	msg.reply_report(ctx, reply).await;
+
+	Ok(())
+}
+
+fn top(
+	lines: &mut Vec<String>,
+	title: &str,
+	ranked: &[(u64, usize)],
+	name: impl Fn(u64) -> String,
+) {
+	if ranked.is_empty() {
+		return;
+	}
+
+	lines.push(format!("**{}**", title));
+	lines.extend(
+		ranked
+			.iter()
+			.take(TOP_SIZE)
+			.enumerate()
+			.map(|(i, (id, count))| format!("{}. {}: {}", i + 1, name(*id), count)),
+	);
+}
+
+fn result(i: usize, entry: &HallEntry, author: &str) -> String {
+	let sent = chrono::DateTime::from_timestamp(
+		MessageId::new(entry.message).created_at().unix_timestamp(),
+		0,
+	)
+	.map_or_else(String::new, |sent| sent.format("%Y-%m-%d").to_string());
+
+	let mut snippet = entry
+		.content
+		.chars()
+		.take(SEARCH_SNIPPET)
+		.map(|c| if c == '\n' { ' ' } else { c })
+		.collect::<String>();
+	if entry.content.chars().count() > SEARCH_SNIPPET {
+		snippet.push('…');
+	}
+
+	let mut links = vec![format!(
+		"[Jump](https://discord.com/channels/{}/{}/{})",
+		entry.guild, entry.hall_channel, entry.hall_message
+	)];
+	if !entry.deleted {
+		links.push(format!(
+			"[Original](https://discord.com/channels/{}/{}/{})",
+			entry.guild, entry.channel, entry.message
+		));
+	}
+
+	format!(
+		"{}. **{}** in {}, {}: {}\n{}",
+		i + 1,
+		author,
+		entry.hall,
+		sent,
+		snippet,
+		links.join(" ")
+	)
+}
+
+/// A user mention, or just their id
+fn mention(value: &str) -> Option<u64> {
+	value
+		.trim_start_matches("<@")
+		.trim_start_matches('!')
+		.trim_end_matches('>')
+		.parse()
+		.ok()
+}
+
+/// The start of a day like 2023-09-30, in UTC
+fn date(value: &str) -> Option<i64> {
+	let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+	Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp())
+}
+
+/// When a period like "week" or "30d" starts. `Some(None)` is all of time
+fn since(period: &str) -> Option<Option<i64>> {
+	const DAY: i64 = 60 * 60 * 24;
+
+	let days = match period {
+		"all" => return Some(None),
+		"day" => 1,
+		"week" => 7,
+		"month" => 30,
+		"year" => 365,
+		_ => {
+			let unit = period.chars().last()?;
+			let amount = period[..period.len() - unit.len_utf8()]
+				.parse::<i64>()
+				.ok()
+				.filter(|amount| *amount > 0)?;
+			let days = match unit {
+				'd' => 1,
+				'w' => 7,
+				'm' => 30,
+				'y' => 365,
+				_ => return None,
+			};
+			amount.checked_mul(days)?
+		}
+	};
+
+	Some(Some(
+		chrono::Utc::now().timestamp() - days.checked_mul(DAY)?,
+	))
+}
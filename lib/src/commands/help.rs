@@ -0,0 +1,134 @@
+use crate::prelude::*;
+use serenity::prelude::*;
+
+use super::commander::{CommandInfo, Commander, Invocation, Permission};
+use crate::bot::Bot;
+
+use sirgovan_macros::command;
+
+#[command(
+	aliases = ["commands"],
+	slash,
+	description = "List every command, or explain one of them"
+)]
+async fn help<'a>(
+	&self,
+	ctx: &Context,
+	msg: &'a Invocation<'a>,
+	bot: &Bot,
+	#[description = "The command you want to know more about"] command: Option<String>,
+) -> GovanResult {
+	let commander = &bot.commander;
+	// Slash commands have text versions too, so those are always shown
+	let prefix = bot
+		.data()
+		.await
+		.prefix(msg.guild_id().map(|id| id.get()))
+		.to_string();
+
+	let Some(command) = command else {
+		let mut lines = vec!["**Commands**".to_string()];
+		lines.extend(
+			commander
+				.infos()
+				.map(|info| summary(commander, info, &prefix)),
+		);
+		lines.push(format!(
+			"Use `{}help <command>` to know more about one of them",
+			prefix
+		));
+
+		msg.reply_report(ctx, lines.join("\n")).await;
+		return Ok(());
+	};
+
+	let info = commander
+		.info(command.strip_prefix(&prefix).unwrap_or(&command))
+		.ok_or_else(govanerror::debug_lazy!(
+			log fmt = ("Asked for help with unknown command {}", command),
+			user fmt = ("I don't know any `{}`. Try `{}help` to see them all", command, prefix)
+		))?;
+
+	msg.reply_report(ctx, details(commander, info, &prefix))
+		.await;
+
+	Ok(())
+}
+
+fn aliases(info: &CommandInfo, prefix: &str) -> String {
+	info.aliases
+		.iter()
+		.map(|alias| match info.parent {
+			Some(parent) => format!("`{}{} {}`", prefix, parent, alias),
+			None => format!("`{}{}`", prefix, alias),
+		})
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+/// Commands with subcommands are used through them, so that's their usage
+fn usage(commander: &Commander, info: &CommandInfo) -> String {
+	let subcommands = commander
+		.subcommands(info)
+		.map(|sub| sub.name)
+		.collect::<Vec<_>>();
+
+	if subcommands.is_empty() {
+		info.usage.to_string()
+	} else {
+		format!("{} <{}>", info.path, subcommands.join("|"))
+	}
+}
+
+fn summary(commander: &Commander, info: &CommandInfo, prefix: &str) -> String {
+	let mut line = format!("`{}{}`", prefix, usage(commander, info));
+
+	if !info.aliases.is_empty() {
+		line += &format!(" (also {})", aliases(info, prefix));
+	}
+
+	if !info.description.is_empty() {
+		line += &format!(": {}", info.description);
+	}
+
+	line
+}
+
+fn details(commander: &Commander, info: &CommandInfo, prefix: &str) -> String {
+	let mut lines = vec![format!("`{}{}`", prefix, usage(commander, info))];
+
+	if !info.description.is_empty() {
+		lines.push(info.description.to_string());
+	}
+
+	for option in info.options.iter() {
+		if option.required {
+			lines.push(format!("- `{}`: {}", option.name, option.description));
+		} else {
+			lines.push(format!(
+				"- `{}` (optional): {}",
+				option.name, option.description
+			));
+		}
+	}
+
+	for sub in commander.subcommands(info) {
+		lines.push(format!("- {}", summary(commander, sub, prefix)));
+	}
+
+	if !info.aliases.is_empty() {
+		lines.push(format!("Also known as {}", aliases(info, prefix)));
+	}
+
+	if info.slash {
+		lines.push(format!("Also available as `/{}`", info.path));
+	}
+
+	match info.permission {
+		Permission::Everyone => (),
+		Permission::Admin => lines.push("Only admins can use this".to_string()),
+		Permission::Owner => lines.push("Only my owners can use this".to_string()),
+	}
+
+	lines.join("\n")
+}
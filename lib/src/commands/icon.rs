@@ -1,21 +1,26 @@
 use crate::prelude::*;
 
-use super::commander::Arguments;
+use super::commander::{Argument, Invocation};
 use crate::bot::Bot;
 use serenity::model::prelude::*;
 use serenity::prelude::*;
 
 use sirgovan_macros::command;
 
-#[command]
+#[command(
+	slash,
+	description = "Change the icon of your personal role",
+	usage = "[emoji|url]",
+	cooldown = [user = 30]
+)]
 async fn icon<'a>(
 	&self,
 	ctx: &Context,
-	msg: &'a Message,
-	mut words: Arguments<'a>,
+	msg: &'a Invocation<'a>,
 	_bot: &Bot,
+	#[description = "An emoji or an image url, nothing to reset"] icon: Option<Argument<'a>>,
 ) -> GovanResult {
-	let guild_id = msg.guild_id.ok_or_else(govanerror::debug_lazy!(
+	let guild_id = msg.guild_id().ok_or_else(govanerror::debug_lazy!(
 		log = "Command used outside of guild",
 		user = "You need to be in a guild, silly!"
 	))?;
@@ -34,15 +39,13 @@ async fn icon<'a>(
 		_ => (),
 	}
 
-	let member = msg.member(&ctx).await?;
+	let member = msg.member(ctx).await?;
 
 	let mut role = member.get_unique_role(ctx)?;
 
-	use super::commander::Argument;
 	use data::EmojiType;
 
-	let arg = words.arg();
-	if let Some(Argument::Emoji(EmojiType::Discord(icon))) = arg {
+	if let Some(Argument::Emoji(EmojiType::Discord(icon))) = icon {
 		let emoji_id = icon;
 		let icon = util::url_from_discord_emoji(icon, false);
 
@@ -55,7 +58,7 @@ async fn icon<'a>(
 
 		msg.reply_report(ctx, &format!("Icon set. Enjoy your <:emoji:{}>", emoji_id))
 			.await;
-	} else if let Some(Argument::Emoji(EmojiType::Unicode(icon))) = arg {
+	} else if let Some(Argument::Emoji(EmojiType::Unicode(icon))) = icon {
 		role.set_unicode_icon(ctx, &icon)
 			.await
 			.map_err(govanerror::error_chain!(
@@ -65,7 +68,7 @@ async fn icon<'a>(
 
 		msg.reply_report(ctx, &format!("Icon set. Enjoy your {}", icon))
 			.await
-	} else if let Some(Argument::String(icon)) = arg {
+	} else if let Some(Argument::String(icon)) = icon {
 		role.set_icon(ctx, icon)
 			.await
 			.map_err(govanerror::error_chain!(
@@ -74,7 +77,7 @@ async fn icon<'a>(
 			))?;
 
 		msg.reply_report(ctx, "Icon set. Enjoy").await;
-	} else if arg.is_none() {
+	} else if icon.is_none() {
 		role.reset_icon(ctx)
 			.await
 			.map_err(govanerror::error_chain!(
@@ -1,31 +1,20 @@
 use crate::prelude::*;
 
-use serenity::model::prelude::*;
 use serenity::prelude::*;
 
-use super::commander::Arguments;
+use super::commander::Invocation;
 use crate::bot::Bot;
-use crate::prelude::MessageExt;
 
 use sirgovan_macros::command;
 
-#[command]
+#[command(description = "Shut me down", usage = "[beta]", permission = "owner")]
 async fn quit<'a>(
 	&self,
 	ctx: &Context,
-	msg: &'a Message,
-	mut words: Arguments<'a>,
+	msg: &'a Invocation<'a>,
 	bot: &Bot,
+	param: Option<&'a str>,
 ) -> GovanResult {
-	if msg.author.id != 120881455663415296 {
-		return Err(govanerror::error!(
-			log fmt = ("Attempted illegal !quit by non-owner: {}", msg.author.name),
-			user = "Nuh-uh"
-		));
-	}
-
-	let param = words.string();
-
 	if param.is_none() || (param.is_some_and(|p| p == "beta") && bot.data().await.beta) {
 		msg.reply_report(ctx, "Bye!").await;
 		bot.shutdown().await;
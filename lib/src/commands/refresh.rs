@@ -1,71 +1,82 @@
 use crate::prelude::*;
 
-use serenity::model::prelude::*;
 use serenity::prelude::*;
 
-use super::commander::Arguments;
+use super::commander::Invocation;
 use crate::bot::Bot;
+use crate::data::BotData;
 
 use sirgovan_macros::command;
 
-#[command]
-async fn refresh<'a>(
-	&self,
-	ctx: &Context,
-	msg: &'a Message,
-	mut words: Arguments<'a>,
-	bot: &Bot,
-) -> GovanResult {
-	if msg.author.id != 120881455663415296 {
-		return Err(govanerror::error!(
-			log fmt = ("Attempted illegal !quit by non-owner: {}", msg.author.name),
-			user = "Nuh-uh"
-		));
-	}
+#[command(description = "Reload my configuration files", permission = "owner")]
+async fn refresh<'a>(&self, _ctx: &Context, msg: &'a Invocation<'a>, bot: &Bot) -> GovanResult {
+	Err(bot.commander.bad_subcommand("refresh", msg))
+}
 
-	let mut bot_data = bot.data.write().await;
-	let mut servers_res = None;
-	let mut no_context_res = None;
-	let mut strings_res = None;
+#[command(parent = "refresh", description = "Reload everything")]
+async fn all<'a>(&self, ctx: &Context, msg: &'a Invocation<'a>, bot: &Bot) -> GovanResult {
+	reload(
+		ctx,
+		msg,
+		bot,
+		&[Reload::Servers, Reload::Roles, Reload::Strings],
+	)
+	.await
+}
 
-	match words.string() {
-		Some("all") => {
-			servers_res = Some(bot_data.load_servers());
-			no_context_res = Some(bot_data.load_role_names());
-			strings_res = Some(bot_data.load_strings());
-		}
-		Some("servers") => {
-			servers_res = Some(bot_data.load_servers());
-		}
-		Some("roles") => {
-			no_context_res = Some(bot_data.load_role_names());
-		}
-		Some("strings") => {
-			strings_res = Some(bot_data.load_strings());
-		}
-		Some(_) | None => {
-			msg.reply_report(ctx, "You want [all|servers|roles|strings]")
-				.await;
-			return Ok(());
-		}
-	}
+#[command(parent = "refresh", description = "Reload the server configuration")]
+async fn servers<'a>(&self, ctx: &Context, msg: &'a Invocation<'a>, bot: &Bot) -> GovanResult {
+	reload(ctx, msg, bot, &[Reload::Servers]).await
+}
 
-	let mut problems = Vec::with_capacity(3);
+#[command(parent = "refresh", description = "Reload the no-context role names")]
+async fn roles<'a>(&self, ctx: &Context, msg: &'a Invocation<'a>, bot: &Bot) -> GovanResult {
+	reload(ctx, msg, bot, &[Reload::Roles]).await
+}
 
-	if let Some(Err(e)) = servers_res {
-		e.log();
-		problems.push("servers");
-	}
+#[command(parent = "refresh", description = "Reload the strings I say")]
+async fn strings<'a>(&self, ctx: &Context, msg: &'a Invocation<'a>, bot: &Bot) -> GovanResult {
+	reload(ctx, msg, bot, &[Reload::Strings]).await
+}
+
+enum Reload {
+	Servers,
+	Roles,
+	Strings,
+}
 
-	if let Some(Err(e)) = no_context_res {
-		e.log();
-		problems.push("roles");
+impl Reload {
+	fn name(&self) -> &'static str {
+		match self {
+			Reload::Servers => "servers",
+			Reload::Roles => "roles",
+			Reload::Strings => "strings",
+		}
 	}
 
-	if let Some(Err(e)) = strings_res {
-		e.log();
-		problems.push("strings");
+	fn load(&self, bot_data: &mut BotData) -> GovanResult {
+		match self {
+			Reload::Servers => bot_data.load_servers(),
+			Reload::Roles => bot_data.load_role_names(),
+			Reload::Strings => bot_data.load_strings(),
+		}
 	}
+}
+
+async fn reload(ctx: &Context, msg: &Invocation<'_>, bot: &Bot, what: &[Reload]) -> GovanResult {
+	let problems = {
+		let mut bot_data = bot.data.write().await;
+
+		what.iter()
+			.filter_map(|reload| match reload.load(&mut bot_data) {
+				Ok(()) => None,
+				Err(e) => {
+					e.log();
+					Some(reload.name())
+				}
+			})
+			.collect::<Vec<_>>()
+	};
 
 	if problems.is_empty() {
 		msg.reply_report(ctx, "All done!").await;
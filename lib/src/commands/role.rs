@@ -1,21 +1,15 @@
 use crate::prelude::*;
 
-use super::commander::Arguments;
+use super::commander::Invocation;
 use crate::bot::Bot;
 use serenity::model::prelude::*;
 use serenity::prelude::*;
 
 use sirgovan_macros::command;
 
-#[command]
-async fn role<'a>(
-	&self,
-	ctx: &Context,
-	msg: &'a Message,
-	mut _words: Arguments<'a>,
-	bot: &Bot,
-) -> GovanResult {
-	let guild_id = msg.guild_id.ok_or_else(govanerror::debug_lazy!(
+#[command(slash, description = "Show the current no-context role and its number")]
+async fn role<'a>(&self, ctx: &Context, msg: &'a Invocation<'a>, bot: &Bot) -> GovanResult {
+	let guild_id = msg.guild_id().ok_or_else(govanerror::debug_lazy!(
 		log = "Command used outside of guild",
 		user = "You need to be in a guild, silly!"
 	))?;
@@ -69,3 +63,142 @@ async fn role<'a>(
 
 	Ok(())
 }
+
+const LEADERBOARD_SIZE: usize = 5;
+
+#[command(
+	parent = "role",
+	description = "How many of the no-context role names someone has held"
+)]
+async fn collection<'a>(
+	&self,
+	ctx: &Context,
+	msg: &'a Invocation<'a>,
+	bot: &Bot,
+	#[description = "Whose collection to check, yours by default"] user: Option<UserId>,
+) -> GovanResult {
+	let guild_id = msg.guild_id().ok_or_else(govanerror::debug_lazy!(
+		log = "Command used outside of guild",
+		user = "You need to be in a guild, silly!"
+	))?;
+
+	let (user_id, name) = match user {
+		Some(user_id) => (
+			user_id,
+			util::member_name(ctx, Some(guild_id), user_id).await,
+		),
+		None => (msg.author().id, msg.author().name.clone()),
+	};
+
+	let reply = {
+		let bot_data = bot.data().await;
+
+		match bot_data
+			.role_collections
+			.collection(guild_id.get(), user_id.get())
+		{
+			Some(names) => {
+				let collection = bot_data.role_collection(names);
+
+				let mut reply = format!(
+					"**{}**: {}/{} collected",
+					name, collection.collected, collection.out_of
+				);
+				if !collection.shiny.is_empty() {
+					reply.push_str(&format!(
+						"\n{} shiny: {}",
+						collection.shiny.len(),
+						collection.shiny.join(", ")
+					));
+				}
+				reply
+			}
+			None => format!("{} has never had the role here", name),
+		}
+	};
+
+	msg.reply_report(ctx, reply).await;
+
+	Ok(())
+}
+
+#[command(
+	parent = "role",
+	aliases = ["top"],
+	description = "Who has held the most no-context role names in this server"
+)]
+async fn leaderboard<'a>(&self, ctx: &Context, msg: &'a Invocation<'a>, bot: &Bot) -> GovanResult {
+	let guild_id = msg.guild_id().ok_or_else(govanerror::debug_lazy!(
+		log = "Command used outside of guild",
+		user = "You need to be in a guild, silly!"
+	))?;
+
+	let board = {
+		let bot_data = bot.data().await;
+
+		let mut board = bot_data
+			.role_collections
+			.collectors(guild_id.get())
+			.map(|(user, names)| {
+				let collection = bot_data.role_collection(names);
+				(
+					user,
+					collection.collected,
+					collection.out_of,
+					collection.shiny.len(),
+				)
+			})
+			.collect::<Vec<_>>();
+
+		board.sort_by(|a, b| b.1.cmp(&a.1).then(b.3.cmp(&a.3)).then(a.0.cmp(&b.0)));
+		board.truncate(LEADERBOARD_SIZE);
+		board
+	};
+
+	let users = board.iter().map(|b| b.0).collect::<Vec<_>>();
+	let names = util::member_names(ctx, Some(guild_id), &users).await;
+	let lines = board
+		.iter()
+		.enumerate()
+		.map(|(i, (user, collected, out_of, shiny))| {
+			let mut line = format!("{}. {}: {}/{}", i + 1, names[user], collected, out_of);
+			if *shiny > 0 {
+				line.push_str(&format!(" and {} shiny", shiny));
+			}
+			line
+		})
+		.collect::<Vec<_>>();
+
+	if lines.is_empty() {
+		msg.reply_report(ctx, "Nobody has had the role here yet")
+			.await;
+		return Ok(());
+	}
+
+	msg.reply_report(ctx, format!("**Top collectors**\n{}", lines.join("\n")))
+		.await;
+
+	Ok(())
+}
+
+#[command(
+	parent = "role",
+	description = "Find out who has the no-context role by checking everyone in this server",
+	permission = "admin"
+)]
+async fn reconcile<'a>(&self, ctx: &Context, msg: &'a Invocation<'a>, bot: &Bot) -> GovanResult {
+	let guild_id = msg.guild_id().ok_or_else(govanerror::debug_lazy!(
+		log = "Command used outside of guild",
+		user = "You need to be in a guild, silly!"
+	))?;
+
+	let holders = bot.reconcile_no_context(ctx, guild_id).await?;
+
+	msg.reply_report(
+		ctx,
+		format!("Checked everyone, {} have the role right now", holders),
+	)
+	.await;
+
+	Ok(())
+}
@@ -1,38 +1,174 @@
+use crate::data::rolls::{RollEntry, RollStats};
+use crate::helpers::dice::{Expressions, Rolled};
 use crate::prelude::*;
-use crate::util::random::{self, RandomBag};
-use num_bigint::BigInt;
 use serenity::model::prelude::*;
 use serenity::prelude::*;
 
-use super::commander::Arguments;
+use super::commander::Invocation;
 use crate::bot::Bot;
 
 use sirgovan_macros::command;
 
-#[command]
+#[command(
+	slash,
+	description = "Roll some dice, like 3d6+2, 4d6kh3, d%, d20adv or 2d6!",
+	cooldown = [user = 3]
+)]
 async fn roll<'a>(
 	&self,
 	ctx: &Context,
-	msg: &'a Message,
-	mut words: Arguments<'a>,
+	msg: &'a Invocation<'a>,
 	bot: &Bot,
+	#[description = "What to roll, a d20 by default. Separate rolls with commas"]
+	#[suggest = ["20", "6", "100", "d%", "3d6", "4d6kh3", "d20adv", "d20dis", "2d6!"]]
+	dice: Option<String>,
 ) -> GovanResult {
-	let number = words.big_number().unwrap_or(20.into());
+	let expressions = dice.as_deref().unwrap_or("20").parse::<Expressions>()?;
 
-	if number <= 0.into() {
-		return Err(govanerror::debug!(
-			log fmt = ("Attempted negative roll: {}", number),
-			user = "Dice need to have 1 or more sides, otherwise I don't know where the North is"
-		));
-	}
+	let rolls = expressions.roll();
+
+	// A single die is just its result, like it always was
+	let result = match &rolls[..] {
+		[rolled] if rolled.is_simple() => rolled.total.to_string(),
+		_ => rolls
+			.iter()
+			.map(Rolled::to_string)
+			.collect::<Vec<_>>()
+			.join("\n"),
+	};
+
+	let entry = RollEntry {
+		user: msg.author().id.get(),
+		guild: msg.guild_id().map(GuildId::get),
+		time: chrono::Utc::now().timestamp(),
+		dice: rolls
+			.iter()
+			.flat_map(Rolled::dice)
+			.filter_map(|(sides, die)| {
+				Some((sides.try_into().ok()?, (&die.value).try_into().ok()?))
+			})
+			.collect(),
+	};
+
+	let reply = {
+		let mut bot_data = bot.data.write().await;
+		bot_data.rolls.record(entry);
+		format!("{}{}", result, bot_data.strings.roll.pick())
+	};
+
+	msg.reply_report(ctx, reply).await;
+
+	Ok(())
+}
+
+/// Fewer dice than this and it's not luck, it's just noise
+const LEADERBOARD_MIN_DICE: u64 = 20;
+const LEADERBOARD_SIZE: usize = 5;
+
+fn percent(luck: Option<f64>) -> String {
+	luck.map_or("-".to_string(), |luck| format!("{:.0}%", luck * 100.0))
+}
+
+#[command(
+	parent = "roll",
+	description = "How lucky someone has been, in this server or everywhere in DMs"
+)]
+async fn stats<'a>(
+	&self,
+	ctx: &Context,
+	msg: &'a Invocation<'a>,
+	bot: &Bot,
+	#[description = "Whose rolls to check, yours by default"] user: Option<UserId>,
+) -> GovanResult {
+	let (user_id, name) = match user {
+		Some(user_id) => (
+			user_id,
+			util::member_name(ctx, msg.guild_id(), user_id).await,
+		),
+		None => (msg.author().id, msg.author().name.clone()),
+	};
 
-	let rand = random::from_range(BigInt::from(1_u64)..=number);
+	let stats = bot
+		.data()
+		.await
+		.rolls
+		.stats(user_id.get(), msg.guild_id().map(GuildId::get))
+		.cloned()
+		.unwrap_or_else(RollStats::default);
+
+	if stats.rolls == 0 {
+		msg.reply_report(ctx, format!("{} has never rolled here", name))
+			.await;
+		return Ok(());
+	}
 
 	msg.reply_report(
 		ctx,
-		&format!("{}{}", rand, bot.data().await.strings.roll.pick()),
+		format!(
+			"**{}**: {} rolls, {} dice\nAverage roll: {} of the max\nNatural 1s: {}\nNatural maxes: {}",
+			name,
+			stats.rolls,
+			stats.dice,
+			percent(stats.luck()),
+			stats.ones,
+			stats.maxes
+		),
 	)
 	.await;
 
 	Ok(())
 }
+
+#[command(
+	parent = "roll",
+	aliases = ["top"],
+	description = "The luckiest and unluckiest rollers in this server"
+)]
+async fn leaderboard<'a>(&self, ctx: &Context, msg: &'a Invocation<'a>, bot: &Bot) -> GovanResult {
+	let guild_id = msg.guild_id().ok_or_else(govanerror::debug_lazy!(
+		log = "Roll leaderboard outside of a guild",
+		user = "You need to be in a guild, silly!"
+	))?;
+
+	let board = bot
+		.data()
+		.await
+		.rolls
+		.leaderboard(guild_id.get(), LEADERBOARD_MIN_DICE);
+
+	if board.is_empty() {
+		msg.reply_report(
+			ctx,
+			format!("Nobody has rolled {} dice here yet", LEADERBOARD_MIN_DICE),
+		)
+		.await;
+		return Ok(());
+	}
+
+	let users = board
+		.iter()
+		.take(LEADERBOARD_SIZE)
+		.chain(board.iter().rev().take(LEADERBOARD_SIZE))
+		.map(|(user, _)| *user)
+		.collect::<Vec<_>>();
+	let names = util::member_names(ctx, Some(guild_id), &users).await;
+	let line = |(i, (user, luck)): (usize, &(u64, f64))| {
+		format!("{}. {}: {}", i + 1, names[user], percent(Some(*luck)))
+	};
+
+	let mut lines = vec!["**Luckiest**".to_string()];
+	lines.extend(board.iter().take(LEADERBOARD_SIZE).enumerate().map(line));
+	lines.push("**Unluckiest**".to_string());
+	lines.extend(
+		board
+			.iter()
+			.rev()
+			.take(LEADERBOARD_SIZE)
+			.enumerate()
+			.map(line),
+	);
+
+	msg.reply_report(ctx, lines.join("\n")).await;
+
+	Ok(())
+}
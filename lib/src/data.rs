@@ -1,14 +1,21 @@
 pub mod config;
 pub mod emoji;
+pub mod halls;
 pub mod regex;
+pub mod role_collections;
+pub mod rolls;
+pub mod schedule;
 pub mod servers;
 pub mod strings;
 
 use std::collections::{HashMap, HashSet};
 
+pub use servers::Cooldown;
+pub use servers::Digest;
 pub use servers::EmojiType;
 pub use servers::Hall;
 pub use servers::NoContext;
+pub use servers::Throwback;
 
 use self::strings::{StringBag, StringBagLoose};
 
@@ -38,32 +45,29 @@ pub struct Server {
 	pub main: bool,
 	pub beta: bool,
 	pub nickname: Option<String>,
-	pub pin_amount: usize,
+	pub prefix: Option<String>,
+	pub admin_roles: HashSet<u64>,
+	pub cooldowns: HashMap<String, Cooldown>,
 
 	pub channels: Channels,
 	pub no_context: Option<NoContext>,
 
-	pub hall_of_fame: Option<Hall<{ emoji::PIN }>>,
-	pub hall_of_typo: Option<Hall<{ emoji::WEARY }>>,
-	pub hall_of_vague: Option<Hall<{ emoji::NO_MOUTH }>>,
-	pub hall_of_all: Option<Hall<'\0'>>,
+	pub halls: Vec<Hall>,
+	pub throwback: Option<Throwback>,
 }
 
 impl Server {
-	fn get_emoji<const T: char>(hall: Option<&Hall<T>>) -> Option<EmojiType> {
-		hall.map(|x| x.get_emoji())
+	/// The hall a reaction with this emoji goes to, not counting catch-all halls
+	pub fn hall_for(&self, emoji: &EmojiType) -> Option<&Hall> {
+		self.halls.iter().find(|hall| hall.emojis.contains(emoji))
 	}
 
-	pub fn is_fame_emoji(&self, emoji: &EmojiType) -> bool {
-		Server::get_emoji(self.hall_of_fame.as_ref()).map_or(false, |x| &x == emoji)
+	pub fn catch_all_hall(&self) -> Option<&Hall> {
+		self.halls.iter().find(|hall| hall.catch_all)
 	}
 
-	pub fn is_typo_emoji(&self, emoji: &EmojiType) -> bool {
-		Server::get_emoji(self.hall_of_typo.as_ref()).map_or(false, |x| &x == emoji)
-	}
-
-	pub fn is_vague_emoji(&self, emoji: &EmojiType) -> bool {
-		Server::get_emoji(self.hall_of_vague.as_ref()).map_or(false, |x| &x == emoji)
+	pub fn hall(&self, name: &str) -> Option<&Hall> {
+		self.halls.iter().find(|hall| hall.name == name)
 	}
 }
 
@@ -74,15 +78,15 @@ impl From<servers::Server> for Server {
 			main: value.main,
 			beta: value.beta,
 			nickname: value.nickname,
+			prefix: value.prefix,
+			admin_roles: HashSet::from_iter(value.admin_roles),
+			cooldowns: value.cooldowns,
 
 			channels: value.channels.into(),
 			no_context: value.no_context,
 
-			pin_amount: value.pin_amount,
-			hall_of_fame: value.hall_of_fame,
-			hall_of_typo: value.hall_of_typo,
-			hall_of_vague: value.hall_of_vague,
-			hall_of_all: value.hall_of_all,
+			halls: value.halls,
+			throwback: value.throwback,
 		}
 	}
 }
@@ -177,8 +181,14 @@ impl TryFrom<strings::Strings> for Strings {
 
 pub struct BotData {
 	pub servers: HashMap<u64, Server>,
+	pub owners: HashSet<u64>,
+	pub prefix: String,
 	pub beta: bool,
 	pub strings: Strings,
+	pub rolls: rolls::RollHistory,
+	pub hall_entries: halls::HallEntries,
+	pub role_collections: role_collections::RoleCollections,
+	pub schedule: schedule::Schedule,
 
 	no_context_strings: Vec<String>,
 }
@@ -187,8 +197,14 @@ impl BotData {
 	pub fn new(beta: bool) -> BotData {
 		BotData {
 			servers: HashMap::new(),
+			owners: HashSet::new(),
+			prefix: "!".to_string(),
 			beta,
 			strings: Strings::default(),
+			rolls: rolls::RollHistory::default(),
+			hall_entries: halls::HallEntries::default(),
+			role_collections: role_collections::RoleCollections::default(),
+			schedule: schedule::Schedule::default(),
 			no_context_strings: vec![],
 		}
 	}
@@ -202,6 +218,8 @@ impl BotData {
 
 		let servers: servers::Servers = toml::from_str(&data)?;
 
+		self.owners = HashSet::from_iter(servers.owners);
+		self.prefix = servers.prefix;
 		self.servers = servers
 			.servers
 			.into_iter()
@@ -212,6 +230,38 @@ impl BotData {
 		Ok(())
 	}
 
+	pub fn load_rolls(&mut self) -> GovanResult {
+		self.rolls = rolls::RollHistory::load()?;
+
+		Ok(())
+	}
+
+	pub fn load_hall_entries(&mut self) -> GovanResult {
+		self.hall_entries = halls::HallEntries::load()?;
+
+		Ok(())
+	}
+
+	pub fn load_role_collections(&mut self) -> GovanResult {
+		self.role_collections = role_collections::RoleCollections::load()?;
+
+		Ok(())
+	}
+
+	pub fn load_schedule(&mut self) -> GovanResult {
+		self.schedule = schedule::Schedule::load()?;
+
+		Ok(())
+	}
+
+	/// The command prefix of a server, or the default one in DMs and servers without their own
+	pub fn prefix(&self, guild_id: Option<u64>) -> &str {
+		guild_id
+			.and_then(|id| self.servers.get(&id))
+			.and_then(|server| server.prefix.as_deref())
+			.unwrap_or(&self.prefix)
+	}
+
 	pub fn load_role_names(&mut self) -> GovanResult {
 		use std::fs;
 		use std::path::Path;
@@ -248,4 +298,41 @@ impl BotData {
 			self.no_context_strings.len(),
 		)
 	}
+
+	/// How many of the names in the list a set of held names has, and which are shiny
+	pub fn role_collection<'a>(
+		&self,
+		names: &'a HashSet<String>,
+	) -> role_collections::RoleCollection<'a> {
+		let (listed, shiny): (Vec<_>, Vec<_>) = names
+			.iter()
+			.partition(|name| self.no_context_index(name).0.is_some());
+
+		let mut shiny = shiny.into_iter().map(String::as_str).collect::<Vec<_>>();
+		shiny.sort_unstable();
+
+		role_collections::RoleCollection {
+			collected: listed.len(),
+			out_of: self.no_context_strings.len(),
+			shiny,
+		}
+	}
+}
+
+#[test]
+fn role_collection() {
+	let mut data = BotData::new(false);
+	data.no_context_strings = ["no context", "out of context", "context free"]
+		.map(str::to_string)
+		.to_vec();
+
+	let names = ["no context", "context free", "zebra", "aardvark"]
+		.map(str::to_string)
+		.into_iter()
+		.collect::<HashSet<_>>();
+	let collection = data.role_collection(&names);
+
+	assert_eq!(collection.collected, 2);
+	assert_eq!(collection.out_of, 3);
+	assert_eq!(collection.shiny, vec!["aardvark", "zebra"]);
 }
@@ -10,5 +10,10 @@ pub const FALLBACK_IMAGE: &str = "file_missing.png";
 pub const HTML_DIR: &str = "html";
 pub const ALWAYS_SUNNY_HBS: &str = "titlecard.hbs";
 pub const FAKE_TWITTER_HBS: &str = "tweet.hbs";
+pub const DIGEST_HBS: &str = "digest.hbs";
 
 pub const SAVED_DIR: &str = "saved";
+pub const ROLLS_FILE: &str = "rolls.jsonl";
+pub const HALLS_FILE: &str = "halls.json";
+pub const ROLE_WINS_FILE: &str = "role_wins.jsonl";
+pub const SCHEDULE_FILE: &str = "schedule.json";
@@ -1,13 +1,5 @@
-use super::EmojiType;
-
-pub const PIN: char = '📌';
-pub const NO_MOUTH: char = '😶';
-pub const WEARY: char = '😩';
-
 pub const REPEAT: &str = "🔁";
 pub const REPEAT_ONCE: &str = "🔂";
 pub const VIOLIN: &str = "🎻";
 pub const HEADSTONE: &str = "🪦";
 pub const FIRE_HEART: &str = "❤️‍🔥";
-
-pub const REDDIT_GOLD: EmojiType = EmojiType::Discord(263774481233870848);
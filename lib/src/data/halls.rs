@@ -0,0 +1,511 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::data::{config, EmojiType};
+use crate::prelude::*;
+
+use serenity::model::id::MessageId;
+
+/// A message that made it into a hall, and the post it got there
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HallEntry {
+	pub hall: String,
+	pub guild: u64,
+	pub channel: u64,
+	pub message: u64,
+	/// Nobody when the original was gone before it could be looked at
+	#[serde(default, deserialize_with = "known_user")]
+	pub author: Option<u64>,
+	pub hall_channel: u64,
+	pub hall_message: u64,
+	pub reactors: Vec<u64>,
+	pub time: i64,
+	/// Text of the original message, as it last was
+	#[serde(default)]
+	pub content: String,
+	/// The emoji that got it in
+	#[serde(default)]
+	pub emoji: Option<EmojiType>,
+	/// The original message is gone, the post stays as it last was
+	#[serde(default)]
+	pub deleted: bool,
+	/// It dropped below the threshold soon after getting in, the post is marked as such
+	#[serde(default)]
+	pub retracted: bool,
+}
+
+/// Older backfills stored unknown authors as 0
+fn known_user<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+	Ok(Option::<u64>::deserialize(deserializer)?.filter(|id| *id != 0))
+}
+
+/// Every hall entry ever made, by the id of the message that got in
+#[derive(Debug, Default)]
+pub struct HallEntries {
+	entries: HashMap<u64, Vec<HallEntry>>,
+	/// Messages by the words in them
+	index: HashMap<String, HashSet<u64>>,
+	/// Words of every indexed message, to take it out of the index again
+	indexed: HashMap<u64, Vec<String>>,
+	/// Messages by the hall posts made for them
+	posts: HashMap<u64, u64>,
+}
+
+/// Lowercase words of a text, as they're searched for
+pub fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+	text.split(|c: char| !c.is_alphanumeric())
+		.filter(|word| !word.is_empty())
+		.map(str::to_lowercase)
+}
+
+impl HallEntries {
+	fn path() -> PathBuf {
+		Path::new(config::RESOURCE_PATH)
+			.join(config::SAVED_DIR)
+			.join(config::HALLS_FILE)
+	}
+
+	pub fn load() -> GovanResult<HallEntries> {
+		let data = match std::fs::read_to_string(HallEntries::path()) {
+			Ok(data) => data,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HallEntries::default()),
+			Err(e) => return Err(e.into()),
+		};
+
+		let mut entries = HallEntries::default();
+		for entry in serde_json::from_str::<Vec<HallEntry>>(&data)? {
+			entries.add(entry);
+		}
+
+		Ok(entries)
+	}
+
+	/// Writes every entry back, entries change rarely enough for this to be fine
+	async fn save(&self) -> GovanResult {
+		let data = serde_json::to_string(&self.iter().collect::<Vec<_>>())?;
+		util::write_file(HallEntries::path(), data).await?;
+
+		Ok(())
+	}
+
+	fn add(&mut self, entry: HallEntry) {
+		let message = entry.message;
+		self.posts.insert(entry.hall_message, message);
+		self.entries.entry(message).or_default().push(entry);
+		self.reindex(message);
+	}
+
+	/// Takes out the entries of a message that match, without saving
+	fn take(&mut self, message: u64, matches: impl Fn(&HallEntry) -> bool) {
+		if let Some(entries) = self.entries.get_mut(&message) {
+			for entry in entries.iter().filter(|e| matches(e)) {
+				self.posts.remove(&entry.hall_message);
+			}
+			entries.retain(|e| !matches(e));
+			if entries.is_empty() {
+				self.entries.remove(&message);
+			}
+		}
+		self.reindex(message);
+	}
+
+	fn unindex(&mut self, message: u64) {
+		for word in self.indexed.remove(&message).into_iter().flatten() {
+			if let Some(messages) = self.index.get_mut(&word) {
+				messages.remove(&message);
+				if messages.is_empty() {
+					self.index.remove(&word);
+				}
+			}
+		}
+	}
+
+	fn reindex(&mut self, message: u64) {
+		self.unindex(message);
+
+		let words = self
+			.entries
+			.get(&message)
+			.into_iter()
+			.flatten()
+			.flat_map(|entry| words(&entry.content))
+			.collect::<HashSet<_>>();
+
+		if words.is_empty() {
+			return;
+		}
+
+		for word in words.iter() {
+			self.index.entry(word.clone()).or_default().insert(message);
+		}
+		self.indexed.insert(message, words.into_iter().collect());
+	}
+
+	pub async fn record(&mut self, entry: HallEntry) -> GovanResult {
+		self.add(entry);
+		self.save().await
+	}
+
+	/// Like `record`, but only saves once
+	pub async fn record_all(
+		&mut self,
+		entries: impl IntoIterator<Item = HallEntry>,
+	) -> GovanResult {
+		for entry in entries {
+			self.add(entry);
+		}
+		self.save().await
+	}
+
+	/// Forgets every entry of some halls of a guild for the ones given, the other halls keep
+	/// theirs
+	pub async fn replace_halls(
+		&mut self,
+		guild: u64,
+		halls: &HashSet<String>,
+		entries: impl IntoIterator<Item = HallEntry>,
+	) -> GovanResult {
+		let replaced = |e: &HallEntry| e.guild == guild && halls.contains(&e.hall);
+		let messages = self
+			.iter()
+			.filter(|e| replaced(e))
+			.map(|e| e.message)
+			.collect::<HashSet<_>>();
+
+		for message in messages {
+			self.take(message, replaced);
+		}
+
+		self.record_all(entries).await
+	}
+
+	/// Only saves when the entry is there and changed
+	pub async fn update(
+		&mut self,
+		message: u64,
+		hall: &str,
+		update: impl FnOnce(&mut HallEntry),
+	) -> GovanResult {
+		let Some(entry) = self
+			.entries
+			.get_mut(&message)
+			.and_then(|entries| entries.iter_mut().find(|e| e.hall == hall))
+		else {
+			return Ok(());
+		};
+
+		let before = entry.clone();
+		update(entry);
+		if *entry == before {
+			return Ok(());
+		}
+		self.reindex(message);
+
+		self.save().await
+	}
+
+	pub async fn remove(&mut self, message: u64, hall: &str) -> GovanResult {
+		self.take(message, |e| e.hall == hall);
+		self.save().await
+	}
+
+	/// The entry of a message in a hall, if it's made it in
+	pub fn get(&self, message: u64, hall: &str) -> Option<&HallEntry> {
+		self.entries.get(&message)?.iter().find(|e| e.hall == hall)
+	}
+
+	/// Every hall a message has made it into
+	pub fn of_message(&self, message: u64) -> &[HallEntry] {
+		self.entries.get(&message).map_or(&[], Vec::as_slice)
+	}
+
+	/// The entry a hall post was made for
+	pub fn of_post(&self, hall_message: u64) -> Option<&HallEntry> {
+		let message = self.posts.get(&hall_message)?;
+		self.entries
+			.get(message)?
+			.iter()
+			.find(|e| e.hall_message == hall_message)
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &HallEntry> {
+		self.entries.values().flatten()
+	}
+
+	/// Entries of a guild with every word of the query, newest first. Retracted entries
+	/// aren't found
+	pub fn search(&self, guild: u64, query: &HallQuery) -> Vec<&HallEntry> {
+		let mut messages: Option<HashSet<u64>> = None;
+		for word in words(&query.text) {
+			let found = self.index.get(&word).cloned().unwrap_or_default();
+			messages = Some(match messages {
+				Some(messages) => &messages & &found,
+				None => found,
+			});
+		}
+
+		// Without words to look for, there's nothing to find
+		let Some(messages) = messages else {
+			return vec![];
+		};
+
+		let sent_at = |e: &HallEntry| MessageId::new(e.message).created_at().unix_timestamp();
+
+		let mut results = self
+			.iter()
+			.filter(|e| messages.contains(&e.message))
+			.filter(|e| e.guild == guild && !e.retracted)
+			.filter(|e| query.author.is_none_or(|author| e.author == Some(author)))
+			.filter(|e| query.hall.as_ref().is_none_or(|hall| &e.hall == hall))
+			.filter(|e| query.before.is_none_or(|before| sent_at(e) < before))
+			.filter(|e| query.after.is_none_or(|after| sent_at(e) >= after))
+			.collect::<Vec<_>>();
+
+		results.sort_by(|a, b| b.message.cmp(&a.message).then(a.hall.cmp(&b.hall)));
+		results
+	}
+
+	/// Entries of a guild in the filter's hall and period. Retracted entries are left out, the
+	/// user is left to the caller
+	pub fn filtered<'a>(
+		&'a self,
+		guild: u64,
+		filter: &'a HallFilter,
+	) -> impl Iterator<Item = &'a HallEntry> {
+		self.iter()
+			.filter(move |e| e.guild == guild && !e.retracted)
+			.filter(|e| filter.hall.as_ref().is_none_or(|hall| &e.hall == hall))
+			.filter(|e| filter.since.is_none_or(|since| e.time >= since))
+			.filter(|e| filter.until.is_none_or(|until| e.time < until))
+	}
+
+	/// Who and where gets into the halls of a guild the most. Retracted entries don't count, and
+	/// unknown authors aren't ranked
+	pub fn stats(&self, guild: u64, filter: &HallFilter) -> HallStats {
+		let mut stats = HallStats::default();
+		let mut authors = HashMap::new();
+		let mut reactors = HashMap::new();
+		let mut channels = HashMap::new();
+		let mut months = HashMap::new();
+
+		for entry in self.filtered(guild, filter) {
+			if filter
+				.user
+				.is_some_and(|user| entry.author != Some(user) && entry.reactors.contains(&user))
+			{
+				stats.reacted += 1;
+			}
+
+			if filter.user.is_some_and(|user| entry.author != Some(user)) {
+				continue;
+			}
+
+			stats.entries += 1;
+			if let Some(author) = entry.author {
+				*authors.entry(author).or_default() += 1;
+			}
+			*channels.entry(entry.channel).or_default() += 1;
+			for reactor in entry.reactors.iter() {
+				*reactors.entry(*reactor).or_default() += 1;
+			}
+
+			if let Some(time) = chrono::DateTime::from_timestamp(entry.time, 0) {
+				*months.entry(time.format("%Y-%m").to_string()).or_default() += 1;
+			}
+		}
+
+		stats.authors = HallStats::ranked(authors);
+		stats.reactors = HallStats::ranked(reactors);
+		stats.channels = HallStats::ranked(channels);
+		stats.months = months.into_iter().collect();
+		stats.months.sort();
+
+		stats
+	}
+}
+
+#[derive(Debug, Default)]
+pub struct HallQuery {
+	/// Words that must all be in the message
+	pub text: String,
+	pub author: Option<u64>,
+	pub hall: Option<String>,
+	/// Only messages sent between these timestamps
+	pub before: Option<i64>,
+	pub after: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+pub struct HallFilter {
+	pub hall: Option<String>,
+	/// Only entries by this user, and how many they helped others into
+	pub user: Option<u64>,
+	/// Only entries from this timestamp on
+	pub since: Option<i64>,
+	/// Only entries from before this timestamp
+	pub until: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+pub struct HallStats {
+	pub entries: usize,
+	/// Entries by others that the filtered user reacted to
+	pub reacted: usize,
+	pub authors: Vec<(u64, usize)>,
+	pub reactors: Vec<(u64, usize)>,
+	pub channels: Vec<(u64, usize)>,
+	/// Entries per month, like "2023-09", oldest first
+	pub months: Vec<(String, usize)>,
+}
+
+impl HallStats {
+	/// Highest count first
+	fn ranked(counts: HashMap<u64, usize>) -> Vec<(u64, usize)> {
+		let mut ranked = counts.into_iter().collect::<Vec<_>>();
+		ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+		ranked
+	}
+}
+
+#[test]
+fn hall_stats() {
+	let mut entries = HallEntries::default();
+	let mut add =
+		|message: u64, guild: u64, hall: &str, author: u64, reactors: &[u64], time: i64| {
+			entries.add(HallEntry {
+				hall: hall.to_string(),
+				guild,
+				channel: message % 2 + 1,
+				message,
+				author: Some(author),
+				hall_channel: 50,
+				hall_message: message + 1000,
+				reactors: reactors.to_vec(),
+				time,
+				content: String::new(),
+				emoji: None,
+				deleted: false,
+				retracted: false,
+			})
+		};
+
+	// 2023-09-06 and 2023-10-05
+	add(1, 10, "pins", 100, &[200, 300], 1_694_000_000);
+	add(2, 10, "pins", 100, &[200], 1_696_500_000);
+	add(3, 10, "pins", 200, &[100], 1_696_500_000);
+	add(4, 10, "funny", 300, &[200], 1_696_500_000);
+	add(5, 20, "pins", 100, &[200], 1_696_500_000);
+	add(6, 10, "pins", 100, &[300], 1_696_500_000);
+	entries.entries.get_mut(&4).unwrap()[0].retracted = true;
+	entries.entries.get_mut(&6).unwrap()[0].author = None;
+
+	let pins = |user: Option<u64>, since: Option<i64>, until: Option<i64>| HallFilter {
+		hall: Some("pins".to_string()),
+		user,
+		since,
+		until,
+	};
+
+	// Unknown authors count as entries, but aren't ranked
+	let stats = entries.stats(10, &pins(None, None, None));
+	assert_eq!(stats.entries, 4);
+	assert_eq!(stats.reacted, 0);
+	assert_eq!(stats.authors, vec![(100, 2), (200, 1)]);
+	assert_eq!(stats.reactors, vec![(200, 2), (300, 2), (100, 1)]);
+	assert_eq!(stats.channels, vec![(1, 2), (2, 2)]);
+	assert_eq!(
+		stats.months,
+		vec![("2023-09".to_string(), 1), ("2023-10".to_string(), 3)]
+	);
+
+	let stats = entries.stats(10, &pins(Some(200), None, None));
+	assert_eq!(stats.entries, 1);
+	assert_eq!(stats.reacted, 2);
+	assert_eq!(stats.authors, vec![(200, 1)]);
+
+	assert_eq!(
+		entries
+			.stats(10, &pins(None, Some(1_695_000_000), None))
+			.entries,
+		3
+	);
+	assert_eq!(
+		entries
+			.stats(10, &pins(None, None, Some(1_695_000_000)))
+			.entries,
+		1
+	);
+
+	// Retracted entries don't count
+	assert_eq!(entries.stats(10, &HallFilter::default()).entries, 4);
+
+	// Older backfills stored unknown authors as 0
+	let stored = r#"{"hall":"pins","guild":10,"channel":1,"message":7,"author":0,"hall_channel":50,"hall_message":1007,"reactors":[],"time":0}"#;
+	assert_eq!(
+		serde_json::from_str::<HallEntry>(stored).unwrap().author,
+		None
+	);
+}
+
+#[test]
+fn hall_search() {
+	let mut entries = HallEntries::default();
+	let mut add = |message: u64, guild: u64, hall: &str, author: u64, content: &str| {
+		entries.add(HallEntry {
+			hall: hall.to_string(),
+			guild,
+			channel: 1,
+			message,
+			author: Some(author),
+			hall_channel: 50,
+			hall_message: message + 1000,
+			reactors: vec![],
+			time: 0,
+			content: content.to_string(),
+			emoji: None,
+			deleted: false,
+			retracted: false,
+		})
+	};
+
+	add(1, 10, "pins", 100, "The quick brown fox");
+	add(2, 10, "pins", 200, "a QUICK red fox!");
+	add(2, 10, "funny", 200, "a QUICK red fox!");
+	add(3, 10, "pins", 100, "quick, retract this");
+	add(4, 20, "pins", 100, "quick fox elsewhere");
+	entries.entries.get_mut(&3).unwrap()[0].retracted = true;
+
+	let search = |text: &str, author: Option<u64>, hall: Option<&str>| {
+		let query = HallQuery {
+			text: text.to_string(),
+			author,
+			hall: hall.map(str::to_string),
+			..Default::default()
+		};
+		entries
+			.search(10, &query)
+			.into_iter()
+			.map(|e| (e.message, e.hall.clone()))
+			.collect::<Vec<_>>()
+	};
+	let found = |results: &[(u64, &str)]| {
+		results
+			.iter()
+			.map(|(message, hall)| (*message, hall.to_string()))
+			.collect::<Vec<_>>()
+	};
+
+	// Newest first, then by hall
+	assert_eq!(
+		search("Quick", None, None),
+		found(&[(2, "funny"), (2, "pins"), (1, "pins")])
+	);
+	assert_eq!(search("quick fox", None, None).len(), 3);
+	assert_eq!(search("quick brown", None, None), found(&[(1, "pins")]));
+	assert_eq!(search("fox", Some(100), None), found(&[(1, "pins")]));
+	assert_eq!(search("fox", None, Some("funny")), found(&[(2, "funny")]));
+	assert!(search("retract", None, None).is_empty());
+	assert!(search("quick zebra", None, None).is_empty());
+	assert!(search("!!!", None, None).is_empty());
+}
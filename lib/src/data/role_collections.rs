@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::data::config;
+use crate::prelude::*;
+
+/// Someone getting the no-context role, and the name it was given with them holding it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RoleWin {
+	pub user: u64,
+	pub guild: u64,
+	pub time: i64,
+	pub name: String,
+}
+
+/// What the names someone has held add up to, next to the names in the list
+pub struct RoleCollection<'a> {
+	pub collected: usize,
+	pub out_of: usize,
+	/// Names that aren't in the list, sorted
+	pub shiny: Vec<&'a str>,
+}
+
+/// Every no-context role name anyone has held, kept on disk and collected in memory
+#[derive(Debug, Default)]
+pub struct RoleCollections {
+	/// Names held by each user of each guild
+	guilds: HashMap<u64, HashMap<u64, HashSet<String>>>,
+	/// When each user of each guild last got the role
+	last_wins: HashMap<u64, HashMap<u64, i64>>,
+}
+
+impl RoleCollections {
+	fn path() -> PathBuf {
+		Path::new(config::RESOURCE_PATH)
+			.join(config::SAVED_DIR)
+			.join(config::ROLE_WINS_FILE)
+	}
+
+	pub fn load() -> GovanResult<RoleCollections> {
+		let mut collections = RoleCollections::default();
+
+		let data = match std::fs::read_to_string(RoleCollections::path()) {
+			Ok(data) => data,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(collections),
+			Err(e) => return Err(e.into()),
+		};
+
+		for line in data.lines().filter(|line| !line.is_empty()) {
+			match serde_json::from_str::<RoleWin>(line) {
+				Ok(win) => collections.add(&win),
+				Err(e) => logger::error_fmt!("Skipping broken role win {}: {}", line, e),
+			}
+		}
+
+		Ok(collections)
+	}
+
+	fn add(&mut self, win: &RoleWin) {
+		self.guilds
+			.entry(win.guild)
+			.or_default()
+			.entry(win.user)
+			.or_default()
+			.insert(win.name.clone());
+
+		let last = self
+			.last_wins
+			.entry(win.guild)
+			.or_default()
+			.entry(win.user)
+			.or_default();
+		*last = (*last).max(win.time);
+	}
+
+	/// Collects a name right away, it's written to disk in the background
+	pub fn record(&mut self, win: RoleWin) {
+		self.add(&win);
+
+		tokio::spawn(async move {
+			if let Err(e) = RoleCollections::append(&win).await {
+				logger::error_fmt!("Could not save role win: {}", e);
+			}
+		});
+	}
+
+	async fn append(win: &RoleWin) -> anyhow::Result<()> {
+		use tokio::io::AsyncWriteExt;
+
+		let path = RoleCollections::path();
+		if let Some(dir) = path.parent() {
+			tokio::fs::create_dir_all(dir).await?;
+		}
+
+		let mut line = serde_json::to_string(win)?;
+		line.push('\n');
+
+		tokio::fs::OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(path)
+			.await?
+			.write_all(line.as_bytes())
+			.await?;
+
+		Ok(())
+	}
+
+	/// Every name a user has held in a guild
+	pub fn collection(&self, guild: u64, user: u64) -> Option<&HashSet<String>> {
+		self.guilds.get(&guild)?.get(&user)
+	}
+
+	/// When a user last got the role in a guild
+	pub fn last_win(&self, guild: u64, user: u64) -> Option<i64> {
+		self.last_wins.get(&guild)?.get(&user).copied()
+	}
+
+	/// When anyone last got the role in a guild
+	pub fn last_pick(&self, guild: u64) -> Option<i64> {
+		self.last_wins.get(&guild)?.values().max().copied()
+	}
+
+	/// Who got the role last in a guild, and should still have it
+	pub fn holder(&self, guild: u64) -> Option<u64> {
+		self.last_wins
+			.get(&guild)?
+			.iter()
+			.max_by_key(|(user, time)| (**time, **user))
+			.map(|(user, _)| *user)
+	}
+
+	/// Everyone in a guild who has held the role, with the names they held
+	pub fn collectors(&self, guild: u64) -> impl Iterator<Item = (u64, &HashSet<String>)> {
+		self.guilds
+			.get(&guild)
+			.into_iter()
+			.flatten()
+			.map(|(user, names)| (*user, names))
+	}
+}
+
+#[test]
+fn role_collections() {
+	let win = |user: u64, guild: u64, time: i64, name: &str| RoleWin {
+		user,
+		guild,
+		time,
+		name: name.to_string(),
+	};
+
+	let mut collections = RoleCollections::default();
+	collections.add(&win(1, 10, 100, "no context"));
+	collections.add(&win(2, 10, 200, "out of context"));
+	collections.add(&win(1, 10, 300, "no context"));
+	collections.add(&win(1, 10, 250, "context free"));
+	collections.add(&win(3, 20, 400, "no context"));
+
+	let names = collections.collection(10, 1).unwrap();
+	assert_eq!(names.len(), 2);
+	assert!(names.contains("no context") && names.contains("context free"));
+	assert!(collections.collection(20, 1).is_none());
+
+	// Wins can be read back out of order
+	assert_eq!(collections.last_win(10, 1), Some(300));
+	assert_eq!(collections.last_pick(10), Some(300));
+	assert_eq!(collections.holder(10), Some(1));
+	assert_eq!(collections.holder(20), Some(3));
+
+	let mut collectors = collections
+		.collectors(10)
+		.map(|(user, names)| (user, names.len()))
+		.collect::<Vec<_>>();
+	collectors.sort();
+	assert_eq!(collectors, vec![(1, 2), (2, 1)]);
+}
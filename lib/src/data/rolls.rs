@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::data::config;
+use crate::prelude::*;
+
+/// A single `!roll`. Dice are `(sides, value)`, dice too big to fit aren't recorded
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RollEntry {
+	pub user: u64,
+	pub guild: Option<u64>,
+	pub time: i64,
+	pub dice: Vec<(u64, u64)>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct RollStats {
+	pub rolls: u64,
+	pub dice: u64,
+	/// Dice that could land on a 1 or their max, d1s don't count
+	pub fair_dice: u64,
+	pub ones: u64,
+	pub maxes: u64,
+	luck: f64,
+}
+
+impl RollStats {
+	fn add(&mut self, entry: &RollEntry) {
+		self.rolls += 1;
+		self.dice += entry.dice.len() as u64;
+
+		for &(sides, value) in entry.dice.iter().filter(|(sides, _)| *sides > 1) {
+			self.fair_dice += 1;
+			self.luck += (value - 1) as f64 / (sides - 1) as f64;
+
+			if value == 1 {
+				self.ones += 1;
+			} else if value == sides {
+				self.maxes += 1;
+			}
+		}
+	}
+
+	/// How close to the max rolls land on average, from 0 to 1
+	pub fn luck(&self) -> Option<f64> {
+		(self.fair_dice > 0).then(|| self.luck / self.fair_dice as f64)
+	}
+}
+
+/// Every roll ever made, kept on disk and summarized in memory
+#[derive(Debug, Default)]
+pub struct RollHistory {
+	users: HashMap<u64, RollStats>,
+	guilds: HashMap<u64, HashMap<u64, RollStats>>,
+}
+
+impl RollHistory {
+	fn path() -> PathBuf {
+		Path::new(config::RESOURCE_PATH)
+			.join(config::SAVED_DIR)
+			.join(config::ROLLS_FILE)
+	}
+
+	pub fn load() -> GovanResult<RollHistory> {
+		let mut history = RollHistory::default();
+
+		let data = match std::fs::read_to_string(RollHistory::path()) {
+			Ok(data) => data,
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(history),
+			Err(e) => return Err(e.into()),
+		};
+
+		for line in data.lines().filter(|line| !line.is_empty()) {
+			match serde_json::from_str::<RollEntry>(line) {
+				Ok(entry) => history.add(&entry),
+				Err(e) => logger::error_fmt!("Skipping broken roll entry {}: {}", line, e),
+			}
+		}
+
+		Ok(history)
+	}
+
+	fn add(&mut self, entry: &RollEntry) {
+		self.users.entry(entry.user).or_default().add(entry);
+
+		if let Some(guild) = entry.guild {
+			self.guilds
+				.entry(guild)
+				.or_default()
+				.entry(entry.user)
+				.or_default()
+				.add(entry);
+		}
+	}
+
+	/// Counts a roll right away, it's written to disk in the background
+	pub fn record(&mut self, entry: RollEntry) {
+		self.add(&entry);
+
+		tokio::spawn(async move {
+			if let Err(e) = RollHistory::append(&entry).await {
+				logger::error_fmt!("Could not save roll: {}", e);
+			}
+		});
+	}
+
+	async fn append(entry: &RollEntry) -> anyhow::Result<()> {
+		use tokio::io::AsyncWriteExt;
+
+		let path = RollHistory::path();
+		if let Some(dir) = path.parent() {
+			tokio::fs::create_dir_all(dir).await?;
+		}
+
+		let mut line = serde_json::to_string(entry)?;
+		line.push('\n');
+
+		tokio::fs::OpenOptions::new()
+			.create(true)
+			.append(true)
+			.open(path)
+			.await?
+			.write_all(line.as_bytes())
+			.await?;
+
+		Ok(())
+	}
+
+	/// Stats of a user in a guild, or everywhere
+	pub fn stats(&self, user: u64, guild: Option<u64>) -> Option<&RollStats> {
+		match guild {
+			Some(guild) => self.guilds.get(&guild)?.get(&user),
+			None => self.users.get(&user),
+		}
+	}
+
+	/// Everyone in a guild with enough dice rolled, from luckiest to unluckiest
+	pub fn leaderboard(&self, guild: u64, min_dice: u64) -> Vec<(u64, f64)> {
+		let mut board = self
+			.guilds
+			.get(&guild)
+			.into_iter()
+			.flatten()
+			.filter(|(_, stats)| stats.fair_dice >= min_dice)
+			.filter_map(|(user, stats)| Some((*user, stats.luck()?)))
+			.collect::<Vec<_>>();
+
+		board.sort_by(|a, b| b.1.total_cmp(&a.1));
+		board
+	}
+}
+
+#[test]
+fn roll_stats() {
+	let roll = |user: u64, guild: Option<u64>, dice: Vec<(u64, u64)>| RollEntry {
+		user,
+		guild,
+		time: 0,
+		dice,
+	};
+
+	let mut history = RollHistory::default();
+	history.add(&roll(1, Some(10), vec![(6, 1), (6, 6), (6, 3)]));
+	history.add(&roll(1, Some(10), vec![(1, 1), (20, 20)]));
+	history.add(&roll(1, None, vec![(4, 1)]));
+	history.add(&roll(2, Some(10), vec![(6, 1), (6, 1), (6, 2)]));
+	history.add(&roll(3, Some(10), vec![(20, 11)]));
+
+	let stats = history.stats(1, Some(10)).unwrap();
+	assert_eq!(stats.rolls, 2);
+	assert_eq!(stats.dice, 5);
+	// The d1 can't be lucky or unlucky
+	assert_eq!(stats.fair_dice, 4);
+	assert_eq!(stats.ones, 1);
+	assert_eq!(stats.maxes, 2);
+	assert_eq!(stats.luck(), Some((0.0 + 1.0 + 0.4 + 1.0) / 4.0));
+
+	let everywhere = history.stats(1, None).unwrap();
+	assert_eq!(everywhere.rolls, 3);
+	assert_eq!(everywhere.ones, 2);
+
+	assert!(history.stats(3, Some(20)).is_none());
+
+	let board = history.leaderboard(10, 3);
+	assert_eq!(
+		board.iter().map(|(user, _)| *user).collect::<Vec<_>>(),
+		vec![1, 2]
+	);
+}
@@ -0,0 +1,71 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::data::config;
+use crate::prelude::*;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// When the scheduled posts of each guild last went out, kept on disk so that restarting
+/// doesn't post them twice
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Schedule {
+	/// The last day each guild got a throwback
+	#[serde(default)]
+	throwbacks: HashMap<u64, String>,
+	/// The first day of the last period each hall of each guild got a digest of
+	#[serde(default)]
+	digests: HashMap<u64, HashMap<String, String>>,
+}
+
+fn parse_date(date: &str) -> Option<NaiveDate> {
+	NaiveDate::parse_from_str(date, DATE_FORMAT).ok()
+}
+
+impl Schedule {
+	fn path() -> PathBuf {
+		Path::new(config::RESOURCE_PATH)
+			.join(config::SAVED_DIR)
+			.join(config::SCHEDULE_FILE)
+	}
+
+	pub fn load() -> GovanResult<Schedule> {
+		match std::fs::read_to_string(Schedule::path()) {
+			Ok(data) => Ok(serde_json::from_str(&data)?),
+			Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Schedule::default()),
+			Err(e) => Err(e.into()),
+		}
+	}
+
+	async fn save(&self) -> GovanResult {
+		util::write_file(Schedule::path(), serde_json::to_string(self)?).await?;
+
+		Ok(())
+	}
+
+	/// The last day a guild got a throwback
+	pub fn last_throwback(&self, guild: u64) -> Option<NaiveDate> {
+		parse_date(self.throwbacks.get(&guild)?)
+	}
+
+	pub async fn throwback_posted(&mut self, guild: u64, day: NaiveDate) -> GovanResult {
+		self.throwbacks
+			.insert(guild, day.format(DATE_FORMAT).to_string());
+		self.save().await
+	}
+
+	/// The first day of the last period a hall got a digest of
+	pub fn last_digest(&self, guild: u64, hall: &str) -> Option<NaiveDate> {
+		parse_date(self.digests.get(&guild)?.get(hall)?)
+	}
+
+	pub async fn digest_posted(&mut self, guild: u64, hall: &str, from: NaiveDate) -> GovanResult {
+		self.digests
+			.entry(guild)
+			.or_default()
+			.insert(hall.to_string(), from.format(DATE_FORMAT).to_string());
+		self.save().await
+	}
+}
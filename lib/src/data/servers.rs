@@ -1,6 +1,6 @@
-use crate::data::emoji;
 use serde::{Deserialize, Serialize};
 use serenity::model::prelude::ReactionType;
+use std::collections::HashMap;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum EmojiType {
@@ -47,24 +47,252 @@ impl From<u64> for EmojiType {
 	}
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Hall<const N: char> {
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Hall {
+	/// How commands refer to this hall
+	pub name: String,
 	pub channel: u64,
-	pub emoji: Option<EmojiType>,
+	#[serde(default)]
+	pub emojis: Vec<EmojiType>,
+	/// Reactions needed to get in
+	#[serde(default = "Hall::default_threshold")]
+	pub threshold: usize,
+	/// Raises the threshold when the channel is busy
+	pub scaling: Option<HallScaling>,
+	/// Whether the author's own reaction counts
+	#[serde(default)]
+	pub self_reactions: bool,
+	/// Reactions from members with these roles don't count
+	#[serde(default)]
+	pub excluded_roles: Vec<u64>,
+	/// Takes every emoji that no other hall or reaction uses
+	#[serde(default)]
+	pub catch_all: bool,
+	/// Shown in the footer instead of the emoji that was used
+	pub icon: Option<EmojiType>,
+	/// What happens to the hall post when the original message is deleted
+	#[serde(default)]
+	pub on_delete: OnDelete,
+	/// Lets a message be taken back out if it drops below the threshold soon after getting in
+	pub retraction: Option<Retraction>,
+	/// How the posts look
+	#[serde(default)]
+	pub embed: HallEmbed,
+	/// Sums up the new entries every week or month
+	pub digest: Option<Digest>,
 }
 
-impl<const N: char> Hall<N> {
-	pub fn get_emoji(&self) -> EmojiType {
-		self.emoji
-			.clone()
-			.unwrap_or(EmojiType::Unicode(N.to_string()))
+impl Hall {
+	fn default_threshold() -> usize {
+		3
 	}
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnDelete {
+	/// Leave the post up, marked as deleted
+	#[default]
+	Keep,
+	/// Delete the post and forget the entry
+	Remove,
+}
+
+/// How the posts of a hall look. The footer and teleport text can use `{message}`, `{channel}`,
+/// `{author}`, `{hall}` and `{reactions}`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HallEmbed {
+	#[serde(default)]
+	pub color: HallColor,
+	#[serde(default = "HallEmbed::default_footer")]
+	pub footer: String,
+	/// Text of the link to the original message
+	#[serde(default = "HallEmbed::default_teleport")]
+	pub teleport: String,
+	/// Show how many reacted
+	#[serde(default)]
+	pub reaction_count: bool,
+	/// Show who reacted
+	#[serde(default)]
+	pub reactor_names: bool,
+}
+
+impl HallEmbed {
+	fn default_footer() -> String {
+		"{message} - {channel}".to_string()
+	}
+
+	fn default_teleport() -> String {
+		"Click to teleport".to_string()
+	}
+}
+
+impl Default for HallEmbed {
+	fn default() -> Self {
+		HallEmbed {
+			color: HallColor::default(),
+			footer: HallEmbed::default_footer(),
+			teleport: HallEmbed::default_teleport(),
+			reaction_count: false,
+			reactor_names: false,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HallColor {
+	/// One of 4096 colours, picked for every post
+	#[default]
+	Random,
+	/// The colour of the author's highest coloured role, random if they have none
+	Role,
+	/// Always this one, like `{ fixed = 0xC0FFEE }`
+	Fixed(u32),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Retraction {
+	/// How long after getting in a message can still be taken out
+	pub minutes: u64,
+	/// Delete the post instead of marking it as retracted
+	#[serde(default)]
+	pub remove: bool,
+}
+
+/// One more reaction is needed for every `per_messages` sent in the channel
+/// in the `minutes` before the message, up to `max`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HallScaling {
+	pub per_messages: usize,
+	#[serde(default = "HallScaling::default_minutes")]
+	pub minutes: u64,
+	pub max: Option<usize>,
+}
+
+impl HallScaling {
+	fn default_minutes() -> u64 {
+		60
+	}
+
+	pub fn threshold(&self, base: usize, recent_messages: usize) -> usize {
+		let scaled = base + recent_messages / self.per_messages.max(1);
+		self.max.map_or(scaled, |max| scaled.min(max.max(base)))
+	}
+}
+
+/// Reposts a hall entry from this day in an earlier year, once a day from `hour:minute` UTC on
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Throwback {
+	pub channel: u64,
+	pub hall: String,
+	pub hour: u32,
+	#[serde(default)]
+	pub minute: u32,
+}
+
+/// Posts a summary of a hall's new entries over the last week or month, at `hour:minute` UTC
+/// on Mondays or on the first of the month
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Digest {
+	pub channel: u64,
+	pub period: DigestPeriod,
+	pub hour: u32,
+	#[serde(default)]
+	pub minute: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestPeriod {
+	Weekly,
+	Monthly,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct NoContext {
 	pub channel: u64,
 	pub role: u64,
+	/// One in this many messages gets picked
+	#[serde(default = "NoContext::default_odds")]
+	pub odds: u64,
+	/// Characters a message needs at least, and at most
+	#[serde(default)]
+	pub min_length: usize,
+	#[serde(default = "NoContext::default_max_length")]
+	pub max_length: usize,
+	/// Messages in these channels are never picked
+	#[serde(default)]
+	pub excluded_channels: Vec<u64>,
+	/// Seconds before someone who got picked can be picked again
+	#[serde(default)]
+	pub cooldown: u64,
+	/// Whether messages with only attachments, or only stickers, can be picked
+	#[serde(default = "NoContext::default_true")]
+	pub attachments: bool,
+	#[serde(default = "NoContext::default_true")]
+	pub stickers: bool,
+	/// Makes a pick likelier the longer there hasn't been one
+	pub pity: Option<Pity>,
+}
+
+impl NoContext {
+	fn default_odds() -> u64 {
+		100
+	}
+
+	fn default_max_length() -> usize {
+		280
+	}
+
+	fn default_true() -> bool {
+		true
+	}
+}
+
+/// The odds double every `minutes` without a pick, up to one in `min_odds`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Pity {
+	pub minutes: u64,
+	#[serde(default = "Pity::default_min_odds")]
+	pub min_odds: u64,
+}
+
+impl Pity {
+	fn default_min_odds() -> u64 {
+		2
+	}
+
+	/// One in how many messages get picked, `minutes_since` the last pick
+	pub fn odds(&self, odds: u64, minutes_since: u64) -> u64 {
+		let doublings = (minutes_since / self.minutes.max(1)).min(u64::BITS as u64) as u32;
+		odds.checked_shr(doublings)
+			.unwrap_or(0)
+			.max(self.min_odds.min(odds))
+			.max(1)
+	}
+}
+
+/// Seconds before a command can be used again, per scope. 0 means no cooldown
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cooldown {
+	#[serde(default)]
+	pub user: Option<u64>,
+	#[serde(default)]
+	pub channel: Option<u64>,
+	#[serde(default)]
+	pub guild: Option<u64>,
+}
+
+impl Cooldown {
+	/// Every scope set in `self` replaces the one in `default`
+	pub fn or(self, default: Cooldown) -> Cooldown {
+		Cooldown {
+			user: self.user.or(default.user),
+			channel: self.channel.or(default.channel),
+			guild: self.guild.or(default.guild),
+		}
+	}
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -80,22 +308,35 @@ pub struct Server {
 	pub main: bool,
 	pub beta: bool,
 	pub nickname: Option<String>,
-	pub pin_amount: usize,
+	pub prefix: Option<String>,
+	#[serde(default)]
+	pub admin_roles: Vec<u64>,
+	#[serde(default)]
+	pub cooldowns: HashMap<String, Cooldown>,
 
 	pub channels: Channels,
 	pub no_context: Option<NoContext>,
 
-	pub hall_of_fame: Option<Hall<{ emoji::PIN }>>,
-	pub hall_of_typo: Option<Hall<{ emoji::WEARY }>>,
-	pub hall_of_vague: Option<Hall<{ emoji::NO_MOUTH }>>,
-	pub hall_of_all: Option<Hall<'\0'>>,
+	#[serde(default)]
+	pub halls: Vec<Hall>,
+	pub throwback: Option<Throwback>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Servers {
+	#[serde(default)]
+	pub owners: Vec<u64>,
+	#[serde(default = "Servers::default_prefix")]
+	pub prefix: String,
 	pub servers: Vec<Server>,
 }
 
+impl Servers {
+	fn default_prefix() -> String {
+		"!".to_string()
+	}
+}
+
 pub enum ServerTomlError {
 	IO(std::io::Error),
 	Toml(toml::de::Error),
@@ -112,3 +353,54 @@ impl From<toml::de::Error> for ServerTomlError {
 		ServerTomlError::Toml(value)
 	}
 }
+
+#[test]
+fn hall_scaling() {
+	let scaling = HallScaling {
+		per_messages: 10,
+		minutes: 60,
+		max: Some(8),
+	};
+
+	assert_eq!(scaling.threshold(3, 0), 3);
+	assert_eq!(scaling.threshold(3, 9), 3);
+	assert_eq!(scaling.threshold(3, 25), 5);
+	assert_eq!(scaling.threshold(3, 1000), 8);
+
+	// A max under the base can't make it easier
+	let low = HallScaling {
+		max: Some(1),
+		..scaling
+	};
+	assert_eq!(low.threshold(3, 1000), 3);
+
+	let unbounded = HallScaling {
+		per_messages: 0,
+		minutes: 60,
+		max: None,
+	};
+	assert_eq!(unbounded.threshold(3, 4), 7);
+}
+
+#[test]
+fn pity_odds() {
+	let pity = Pity {
+		minutes: 30,
+		min_odds: 4,
+	};
+
+	assert_eq!(pity.odds(100, 0), 100);
+	assert_eq!(pity.odds(100, 29), 100);
+	assert_eq!(pity.odds(100, 30), 50);
+	assert_eq!(pity.odds(100, 95), 12);
+	assert_eq!(pity.odds(100, 10_000), 4);
+
+	// Odds already better than the floor aren't made worse
+	assert_eq!(pity.odds(3, 60), 3);
+
+	let reckless = Pity {
+		minutes: 0,
+		min_odds: 0,
+	};
+	assert_eq!(reckless.odds(100, u64::MAX), 1);
+}
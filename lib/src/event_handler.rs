@@ -39,6 +39,61 @@ impl EventHandler for BotEventHandler {
 		}
 	}
 
+	async fn message_update(
+		&self,
+		ctx: Context,
+		_old_if_available: Option<Message>,
+		_new: Option<Message>,
+		event: MessageUpdateEvent,
+	) {
+		if let Err(e) = self.bot.on_message_update(&ctx, &event).await {
+			e.log();
+		}
+	}
+
+	async fn message_delete(
+		&self,
+		ctx: Context,
+		_channel_id: ChannelId,
+		deleted_message_id: MessageId,
+		_guild_id: Option<GuildId>,
+	) {
+		if let Err(e) = self.bot.on_message_delete(&ctx, deleted_message_id).await {
+			e.log();
+		}
+	}
+
+	async fn message_delete_bulk(
+		&self,
+		ctx: Context,
+		_channel_id: ChannelId,
+		multiple_deleted_messages_ids: Vec<MessageId>,
+		_guild_id: Option<GuildId>,
+	) {
+		for message_id in multiple_deleted_messages_ids {
+			if let Err(e) = self.bot.on_message_delete(&ctx, message_id).await {
+				e.log();
+			}
+		}
+	}
+
+	async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+		match interaction {
+			Interaction::Command(command) => {
+				if let Err(e) = self.bot.on_interaction_create(&ctx, &command).await {
+					e.report_interaction(&ctx, &command, Some(&self.bot.data().await.strings))
+						.await;
+				}
+			}
+			Interaction::Autocomplete(autocomplete) => {
+				if let Err(e) = self.bot.on_autocomplete(&ctx, &autocomplete).await {
+					e.log();
+				}
+			}
+			_ => (),
+		}
+	}
+
 	async fn reaction_add(&self, ctx: Context, add_reaction: Reaction) {
 		if let Err(e) = self.bot.on_reaction_add(&ctx, &add_reaction).await {
 			match add_reaction.message(&ctx).await {
@@ -60,4 +115,10 @@ impl EventHandler for BotEventHandler {
 			}
 		}
 	}
+
+	async fn reaction_remove(&self, ctx: Context, removed_reaction: Reaction) {
+		if let Err(e) = self.bot.on_reaction_remove(&ctx, &removed_reaction).await {
+			e.log();
+		}
+	}
 }
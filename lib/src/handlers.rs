@@ -1,3 +1,7 @@
+mod on_interaction_create;
 mod on_message;
+mod on_message_delete;
+mod on_message_update;
 mod on_reaction_add;
+mod on_reaction_remove;
 mod on_ready;
@@ -0,0 +1,74 @@
+use crate::prelude::*;
+
+use crate::bot::Bot;
+
+use colored::Colorize;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+impl Bot {
+	pub async fn on_interaction_create(
+		&self,
+		ctx: &Context,
+		interaction: &CommandInteraction,
+	) -> GovanResult {
+		// Anything we say from here on, errors included, is a followup
+		interaction.defer(ctx).await?;
+
+		let guild_id = interaction.guild_id.ok_or_else(govanerror::debug_lazy!(
+			log = "Slash command used outside of guild",
+			user = "You need to be in a guild, silly!"
+		))?;
+
+		let bot_data = self.data().await;
+		let server = bot_data
+			.servers
+			.get(&guild_id.get())
+			.ok_or_else(govanerror::debug_lazy!(
+				log fmt = ("Slash command in unavailable guild {}", guild_id)
+			))?;
+
+		// Like text commands, nothing is heard in these at all
+		if server
+			.channels
+			.disallowed_listen
+			.contains(&interaction.channel_id.get())
+		{
+			return Err(govanerror::debug!(
+				log fmt = ("Slash command in unlistened channel {}", interaction.channel_id),
+				user = "Not here, buddy"
+			));
+		}
+
+		if server.main {
+			logger::info_fmt!(
+				"{} used /{} @ {}",
+				interaction.user.name.cyan(),
+				interaction.data.name,
+				interaction.channel_id
+			);
+		}
+
+		if !server
+			.channels
+			.allowed_commands
+			.contains(&interaction.channel_id.get())
+		{
+			return Err(govanerror::debug!(
+				log fmt = ("Slash command in disallowed channel {}", interaction.channel_id),
+				user = "Not here, buddy"
+			));
+		}
+
+		drop(bot_data); // Unlock data. This isn't great...
+		self.commander.interact(ctx, interaction, self).await
+	}
+
+	pub async fn on_autocomplete(
+		&self,
+		ctx: &Context,
+		interaction: &CommandInteraction,
+	) -> GovanResult {
+		self.commander.autocomplete(ctx, interaction).await
+	}
+}
@@ -109,20 +109,25 @@ impl Bot {
 
 			// From here on we're for sure allowed to listen into messages
 
-			if self.can_remove_context(ctx, msg, server) && util::random::one_in(100) {
-				if let Err(e) = self.remove_context(ctx, msg, server).await {
+			let remove_context = self.can_remove_context(ctx, msg, server)
+				&& self.pick_no_context(msg, server, &bot_data);
+			let allowed_commands = server
+				.channels
+				.allowed_commands
+				.contains(&msg.channel_id.get());
+
+			// Removing context writes down who got the role
+			drop(bot_data);
+
+			if remove_context {
+				if let Err(e) = self.remove_context(ctx, msg).await {
 					e.log(); // No propagation, we keep gooooing
 				};
 			}
 
 			// TODO Donk Solbs easter egg goes here
 
-			if server
-				.channels
-				.allowed_commands
-				.contains(&msg.channel_id.get())
-			{
-				drop(bot_data); // Unlock data. This isn't great...
+			if allowed_commands {
 				self.commander.parse(ctx, msg, self).await?;
 			}
 		}
@@ -0,0 +1,24 @@
+use crate::prelude::*;
+
+use crate::bot::Bot;
+
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+impl Bot {
+	pub async fn on_message_delete(&self, ctx: &Context, message_id: MessageId) -> GovanResult {
+		if self
+			.data()
+			.await
+			.hall_entries
+			.of_message(message_id.get())
+			.is_empty()
+		{
+			return Err(govanerror::debug!(
+				// log = "Deleted message is in no hall"
+			));
+		}
+
+		self.unpin_deleted(ctx, message_id).await
+	}
+}
@@ -0,0 +1,30 @@
+use crate::prelude::*;
+
+use crate::bot::Bot;
+
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+impl Bot {
+	pub async fn on_message_update(
+		&self,
+		ctx: &Context,
+		event: &MessageUpdateEvent,
+	) -> GovanResult {
+		if self
+			.data()
+			.await
+			.hall_entries
+			.of_message(event.id.get())
+			.is_empty()
+		{
+			return Err(govanerror::debug!(
+				// log = "Edited message is in no hall"
+			));
+		}
+
+		let msg = event.channel_id.message(ctx, event.id).await?;
+
+		self.update_pins(ctx, msg).await
+	}
+}
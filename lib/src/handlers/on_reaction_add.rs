@@ -1,7 +1,9 @@
 use crate::prelude::*;
 
 use crate::bot::Bot;
-use crate::data::EmojiType;
+use crate::data::halls::HallEntry;
+use crate::data::{BotData, EmojiType, Hall};
+use crate::helpers::react_locks::Requirement;
 use crate::util::error::GovanResult;
 
 use colored::Colorize;
@@ -72,57 +74,17 @@ impl Bot {
 				with_context: bool,
 				verified_role: Option<u64>,
 			},
-			Pin {
-				destination_id: u64,
-				required: usize,
-				emoji_override: Option<EmojiType>,
-			},
+			Pin(Box<Hall>),
 			AlwaysSunny,
 			None,
 		}
 
 		let action = {
 			let emoji: EmojiType = EmojiType::from(&add_reaction.emoji);
-			let required = server.pin_amount;
 
 			// Decide what to do here
-			if server.is_fame_emoji(&emoji) {
-				let hall = server.hall_of_fame.as_ref().unwrap();
-				let channel_id = hall.channel;
-
-				Action::Pin {
-					destination_id: channel_id,
-					required,
-					emoji_override: {
-						if let EmojiType::Unicode(emoji) = hall.get_emoji() {
-							if emoji.contains(crate::data::emoji::PIN) {
-								Some(crate::data::emoji::REDDIT_GOLD)
-							} else {
-								None
-							}
-						} else {
-							None
-						}
-					},
-				}
-			} else if server.is_typo_emoji(&emoji) {
-				let hall = server.hall_of_typo.as_ref().unwrap();
-				let channel_id = hall.channel;
-
-				Action::Pin {
-					destination_id: channel_id,
-					required,
-					emoji_override: None,
-				}
-			} else if server.is_vague_emoji(&emoji) {
-				let hall = server.hall_of_vague.as_ref().unwrap();
-				let channel_id = hall.channel;
-
-				Action::Pin {
-					destination_id: channel_id,
-					required,
-					emoji_override: None,
-				}
+			if let Some(hall) = server.hall_for(&emoji) {
+				Action::Pin(Box::new(hall.clone()))
 			} else {
 				// One-offs
 				let action = match emoji {
@@ -143,25 +105,17 @@ impl Bot {
 					EmojiType::Discord(_) => Action::None,
 				};
 
-				// Hall-of-all
-				if matches!(action, Action::None) {
-					if let Some(hall_of_all) = server.hall_of_all.as_ref() {
-						let channel_id = hall_of_all.channel;
-
-						Action::Pin {
-							destination_id: channel_id,
-							required,
-							emoji_override: None,
-						}
-					} else {
-						action
-					}
-				} else {
-					action
+				// Anything left over goes to the catch-all hall, if there is one
+				match (action, server.catch_all_hall()) {
+					(Action::None, Some(hall)) => Action::Pin(Box::new(hall.clone())),
+					(action, _) => action,
 				}
 			}
 		};
 
+		// Pinning needs to write down the hall entry
+		drop(bot_data);
+
 		match action {
 			Action::None => Ok(()),
 			Action::DarkSouls(souls_type) => {
@@ -292,24 +246,43 @@ impl Bot {
 				self.maybe_iasip(ctx, &msg).await?;
 				Ok(())
 			}
-			Action::Pin {
-				destination_id,
-				required,
-				emoji_override,
-			} => {
+			Action::Pin(hall) => {
 				// No pinning your own messages, bot
 				if msg.author.id == ctx.cache.current_user().id {
 					return Err(govanerror::debug!(log = "Won't pin myself"));
 				}
 
-				if destination_id == 0 {
+				if hall.channel == 0 {
 					return Err(govanerror::error!(
-						log = "Misconfigured destination_id is 0"
+						log fmt = ("Misconfigured hall {} has channel 0", hall.name)
 					));
 				}
 
-				{
+				let recorded =
+					|data: &BotData| data.hall_entries.get(msg.id.get(), &hall.name).is_some();
+				let already_in = govanerror::debug_lazy!(
+					log fmt = ("Message {} is already in hall {}", msg.id, hall.name)
+				);
+
+				// Before anything costly, most reactions come after the message got in
+				if recorded(&*self.data().await) {
+					return Err(already_in());
+				}
+
+				let required = Requirement {
+					amount: self.hall_threshold(ctx, &msg, &hall).await,
+					self_reactions: hall.self_reactions,
+					excluded_roles: &hall.excluded_roles,
+				};
+
+				let reactors = {
 					let pin_lock = self.pin_lock().await;
+
+					// The reaction I leave behind can be removed, the entry can't
+					if recorded(&*self.data().await) {
+						return Err(already_in());
+					}
+
 					pin_lock
 						.locked_react(
 							ctx,
@@ -319,11 +292,28 @@ impl Bot {
 							Some(required),
 							None,
 						)
-						.await?;
-				}
-				let channel = ChannelId::new(destination_id).to_channel(&ctx).await?;
+						.await?
+				};
+
+				let mut entry = HallEntry {
+					hall: hall.name.clone(),
+					guild: this_channel.guild_id.get(),
+					channel: msg.channel_id.get(),
+					message: msg.id.get(),
+					author: Some(msg.author.id.get()),
+					hall_channel: hall.channel,
+					hall_message: 0,
+					reactors: reactors.iter().map(|user| user.id.get()).collect(),
+					time: chrono::Utc::now().timestamp(),
+					content: msg.content.clone(),
+					emoji: Some(EmojiType::from(&add_reaction.emoji)),
+					deleted: false,
+					retracted: false,
+				};
+
+				let channel = ChannelId::new(hall.channel).to_channel(&ctx).await?;
 				let channel = channel.guild().ok_or_else(govanerror::error_lazy!(
-				  log fmt = ("Channel {} is misconfigured with {:?} pin", destination_id, emoji_override),
+				  log fmt = ("Channel {} is misconfigured for hall {}", hall.channel, hall.name),
 				  user = "< This guy's creator has fucked up"
 				))?;
 
@@ -332,10 +322,12 @@ impl Bot {
 					.await
 					.map_err(|e| e.with_user_string_weak("Oh no, problems"))?;
 
-				self.maybe_pin(ctx, msg, add_reaction, channel, emoji_override)
+				let pin = self
+					.maybe_pin(ctx, msg, add_reaction, channel, &hall, &entry.reactors)
 					.await?;
 
-				Ok(())
+				entry.hall_message = pin.id.get();
+				self.data.write().await.hall_entries.record(entry).await
 			}
 		}
 	}
@@ -0,0 +1,33 @@
+use crate::prelude::*;
+
+use crate::bot::Bot;
+
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+impl Bot {
+	pub async fn on_reaction_remove(
+		&self,
+		ctx: &Context,
+		removed_reaction: &Reaction,
+	) -> GovanResult {
+		// I remove my own reactions when their time is up
+		if removed_reaction.user_id == Some(ctx.cache.current_user().id) {
+			return Err(govanerror::debug!(
+				// log = "No dispatching reaction removals on self"
+			));
+		}
+
+		let msg = removed_reaction.message(ctx).await?;
+
+		// One failing shouldn't keep the other from happening
+		let removed = self
+			.pin_lock()
+			.await
+			.reaction_removed(ctx, removed_reaction, &msg)
+			.await;
+		let retracted = self.maybe_retract(ctx, removed_reaction, &msg).await;
+
+		removed.and(retracted)
+	}
+}
@@ -1,4 +1,5 @@
 use std::convert::Infallible;
+use std::sync::atomic::Ordering;
 
 use crate::bot::Bot;
 use crate::util::logger;
@@ -14,6 +15,8 @@ impl Bot {
 			.set_max_messages(10_000_000 / std::mem::size_of::<Message>());
 
 		self.randomize_self().await;
+		self.register_slash_commands(&ctx).await;
+		self.reconcile_all_no_context(&ctx).await;
 
 		logger::info_fmt!(
 			"Am ready :). I am {}. I am in {} mode",
@@ -27,4 +30,52 @@ impl Bot {
 
 		None
 	}
+
+	async fn register_slash_commands(&self, ctx: &Context) {
+		let servers = self
+			.data()
+			.await
+			.servers
+			.keys()
+			.copied()
+			.collect::<Vec<_>>();
+
+		for id in servers {
+			match GuildId::new(id)
+				.set_commands(ctx, self.commander.slash_commands())
+				.await
+			{
+				Ok(commands) => {
+					logger::debug_fmt!("Registered {} slash commands in {}", commands.len(), id)
+				}
+				Err(e) => logger::error_fmt!("Could not register slash commands in {}: {}", id, e),
+			}
+		}
+	}
+
+	/// Finds out who has the no-context role everywhere, so picks don't have to. Reconnecting
+	/// makes me ready again, but what I tracked since is still right
+	async fn reconcile_all_no_context(&self, ctx: &Context) {
+		if self.no_context_reconciled.swap(true, Ordering::Relaxed) {
+			return;
+		}
+
+		let servers = self
+			.data()
+			.await
+			.servers
+			.values()
+			.filter(|server| server.no_context.as_ref().is_some_and(|nc| nc.role != 0))
+			.map(|server| server.id)
+			.collect::<Vec<_>>();
+
+		for id in servers {
+			match self.reconcile_no_context(ctx, GuildId::new(id)).await {
+				Ok(holders) => {
+					logger::debug_fmt!("{} have the no-context role in {}", holders, id)
+				}
+				Err(e) => e.log(),
+			}
+		}
+	}
 }
@@ -1,3 +1,4 @@
+pub mod dice;
 pub mod discord_content_conversion;
 pub mod handlebars;
 pub mod react_locks;
@@ -0,0 +1,441 @@
+use num_bigint::BigInt;
+
+use crate::util::error::{self as govanerror, GovanResult};
+use crate::util::random;
+
+/// Most dice a single message can roll, explosions included
+const MAX_DICE: usize = 100;
+/// Most comma-separated rolls in a single message
+const MAX_ROLLS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Keep {
+	All,
+	Highest(usize),
+	Lowest(usize),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Dice {
+	count: usize,
+	sides: BigInt,
+	keep: Keep,
+	explode: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Term {
+	Dice(Dice),
+	Constant(BigInt),
+}
+
+/// A single roll, like `4d6kh3+2`
+#[derive(Debug, PartialEq, Eq)]
+pub struct Expression {
+	text: String,
+	terms: Vec<(bool, Term)>,
+}
+
+/// Some rolls, separated by commas
+#[derive(Debug, PartialEq, Eq)]
+pub struct Expressions(Vec<Expression>);
+
+struct Parser<'a> {
+	text: &'a str,
+	chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+	fn new(text: &'a str) -> Parser<'a> {
+		Parser {
+			text,
+			chars: text.chars().peekable(),
+		}
+	}
+
+	fn error(&self, reason: &str) -> govanerror::GovanError {
+		govanerror::debug!(
+			log fmt = ("Invalid dice {}: {}", self.text, reason),
+			user fmt = ("I can't roll `{}`: {}", self.text, reason)
+		)
+	}
+
+	fn number(&mut self) -> Option<BigInt> {
+		let mut digits = String::new();
+		while let Some(digit) = self.chars.next_if(char::is_ascii_digit) {
+			digits.push(digit);
+		}
+
+		digits.parse().ok()
+	}
+
+	fn amount(&mut self, what: &str) -> GovanResult<usize> {
+		self.number()
+			.and_then(|n| usize::try_from(n).ok())
+			.filter(|n| *n > 0 && *n <= MAX_DICE)
+			.ok_or_else(|| self.error(&format!("{} must be between 1 and {}", what, MAX_DICE)))
+	}
+
+	fn keyword(&mut self, keyword: &str) -> bool {
+		let rest = self.chars.clone().collect::<String>();
+		if rest.starts_with(keyword) {
+			self.chars.nth(keyword.chars().count() - 1);
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Advantage and disadvantage roll a single die twice
+	fn advantage(&self, count: usize) -> GovanResult<usize> {
+		match count {
+			1 => Ok(2),
+			_ => Err(self.error("Advantage is for a single die")),
+		}
+	}
+
+	fn dice(&mut self, count: Option<BigInt>) -> GovanResult<Dice> {
+		let count = match count {
+			None => 1,
+			Some(count) => usize::try_from(count)
+				.ok()
+				.filter(|n| *n > 0 && *n <= MAX_DICE)
+				.ok_or_else(|| {
+					self.error(&format!("You can roll between 1 and {} dice", MAX_DICE))
+				})?,
+		};
+
+		let sides = if self.chars.next_if_eq(&'%').is_some() {
+			BigInt::from(100)
+		} else {
+			self.number()
+				.filter(|sides| *sides > 0.into())
+				.ok_or_else(|| self.error("Dice need to have 1 or more sides"))?
+		};
+
+		let mut dice = Dice {
+			count,
+			sides,
+			keep: Keep::All,
+			explode: false,
+		};
+
+		loop {
+			if self.keyword("kl") {
+				dice.keep = Keep::Lowest(self.amount("Dice kept")?);
+			} else if self.keyword("kh") || self.keyword("k") {
+				dice.keep = Keep::Highest(self.amount("Dice kept")?);
+			} else if self.keyword("adv") {
+				dice.count = self.advantage(dice.count)?;
+				dice.keep = Keep::Highest(1);
+			} else if self.keyword("dis") {
+				dice.count = self.advantage(dice.count)?;
+				dice.keep = Keep::Lowest(1);
+			} else if self.chars.next_if_eq(&'!').is_some() {
+				if dice.sides == 1.into() {
+					return Err(self.error("A d1 would explode forever"));
+				}
+				dice.explode = true;
+			} else {
+				break;
+			}
+		}
+
+		match dice.keep {
+			Keep::Highest(n) | Keep::Lowest(n) if n > dice.count => {
+				Err(self.error("You can't keep more dice than you roll"))
+			}
+			_ => Ok(dice),
+		}
+	}
+
+	fn term(&mut self) -> GovanResult<Term> {
+		let number = self.number();
+
+		if self.chars.next_if_eq(&'d').is_some() {
+			Ok(Term::Dice(self.dice(number)?))
+		} else {
+			number
+				.map(Term::Constant)
+				.ok_or_else(|| self.error("Expected a number or some dice"))
+		}
+	}
+
+	fn expression(mut self) -> GovanResult<Expression> {
+		let mut terms = vec![];
+		let mut positive = self.chars.next_if_eq(&'-').is_none();
+		self.chars.next_if_eq(&'+');
+
+		loop {
+			terms.push((positive, self.term()?));
+
+			match self.chars.next() {
+				Some('+') => positive = true,
+				Some('-') => positive = false,
+				Some(c) => return Err(self.error(&format!("Unexpected {}", c))),
+				None => break,
+			}
+		}
+
+		// A lone number is how many sides the die has
+		if let [(positive, Term::Constant(sides))] = &terms[..] {
+			if !positive || *sides <= 0.into() {
+				return Err(self.error("Dice need to have 1 or more sides"));
+			}
+
+			terms = vec![(
+				true,
+				Term::Dice(Dice {
+					count: 1,
+					sides: sides.clone(),
+					keep: Keep::All,
+					explode: false,
+				}),
+			)];
+		}
+
+		Ok(Expression {
+			text: self.text.to_string(),
+			terms,
+		})
+	}
+}
+
+impl std::str::FromStr for Expressions {
+	type Err = govanerror::GovanError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let text = s
+			.to_lowercase()
+			.chars()
+			.filter(|c| !c.is_whitespace())
+			.collect::<String>();
+
+		let expressions = text
+			.split(',')
+			.map(|part| Parser::new(part).expression())
+			.collect::<GovanResult<Vec<_>>>()?;
+
+		if expressions.len() > MAX_ROLLS {
+			return Err(govanerror::debug!(
+				log fmt = ("Too many rolls: {}", expressions.len()),
+				user fmt = ("I can only do {} rolls at a time", MAX_ROLLS)
+			));
+		}
+
+		let dice = expressions
+			.iter()
+			.flat_map(|e| e.terms.iter())
+			.map(|(_, term)| match term {
+				Term::Dice(dice) => dice.count,
+				Term::Constant(_) => 0,
+			})
+			.sum::<usize>();
+
+		if dice > MAX_DICE {
+			return Err(govanerror::debug!(
+				log fmt = ("Too many dice: {}", dice),
+				user fmt = ("I only have {} dice", MAX_DICE)
+			));
+		}
+
+		Ok(Expressions(expressions))
+	}
+}
+
+/// A die that was rolled
+#[derive(Debug)]
+pub struct Die {
+	pub value: BigInt,
+	pub kept: bool,
+	/// Rolled its maximum and caused another die to be rolled
+	pub exploded: bool,
+}
+
+#[derive(Debug)]
+pub enum RolledTerm {
+	Dice { sides: BigInt, dice: Vec<Die> },
+	Constant(BigInt),
+}
+
+/// The result of an expression
+#[derive(Debug)]
+pub struct Rolled {
+	pub text: String,
+	pub terms: Vec<(bool, RolledTerm)>,
+	pub total: BigInt,
+}
+
+impl Dice {
+	fn roll(&self, budget: &mut usize) -> Vec<Die> {
+		let mut dice = Vec::with_capacity(self.count);
+
+		for _ in 0..self.count {
+			loop {
+				let value = random::from_range(BigInt::from(1)..=self.sides.clone());
+				let exploded = self.explode && value == self.sides && *budget > 0;
+				dice.push(Die {
+					value,
+					kept: true,
+					exploded,
+				});
+
+				if !exploded {
+					break;
+				}
+				*budget -= 1;
+			}
+		}
+
+		let mut order = (0..dice.len()).collect::<Vec<_>>();
+		order.sort_by(|a, b| dice[*a].value.cmp(&dice[*b].value));
+
+		let dropped = match self.keep {
+			Keep::All => &[][..],
+			Keep::Highest(n) => &order[..dice.len().saturating_sub(n)],
+			Keep::Lowest(n) => &order[n.min(dice.len())..],
+		};
+
+		for i in dropped {
+			dice[*i].kept = false;
+		}
+
+		dice
+	}
+}
+
+impl Expressions {
+	pub fn roll(&self) -> Vec<Rolled> {
+		let mut budget = MAX_DICE;
+
+		self.0
+			.iter()
+			.map(|expression| {
+				let terms = expression
+					.terms
+					.iter()
+					.map(|(positive, term)| {
+						let term = match term {
+							Term::Dice(dice) => RolledTerm::Dice {
+								sides: dice.sides.clone(),
+								dice: dice.roll(&mut budget),
+							},
+							Term::Constant(n) => RolledTerm::Constant(n.clone()),
+						};
+						(*positive, term)
+					})
+					.collect::<Vec<_>>();
+
+				let total = terms
+					.iter()
+					.map(|(positive, term)| {
+						let value = match term {
+							RolledTerm::Dice { dice, .. } => dice
+								.iter()
+								.filter(|die| die.kept)
+								.map(|die| &die.value)
+								.sum::<BigInt>(),
+							RolledTerm::Constant(n) => n.clone(),
+						};
+						if *positive {
+							value
+						} else {
+							-value
+						}
+					})
+					.sum();
+
+				Rolled {
+					text: expression.text.clone(),
+					terms,
+					total,
+				}
+			})
+			.collect()
+	}
+}
+
+impl Rolled {
+	/// Every die rolled, with how many sides it had
+	pub fn dice(&self) -> impl Iterator<Item = (&BigInt, &Die)> {
+		self.terms.iter().flat_map(|(_, term)| match term {
+			RolledTerm::Dice { sides, dice } => dice.iter().map(|die| (sides, die)).collect(),
+			RolledTerm::Constant(_) => vec![],
+		})
+	}
+
+	/// Just one die, nothing else to show but the result
+	pub fn is_simple(&self) -> bool {
+		matches!(&self.terms[..], [(true, RolledTerm::Dice { dice, .. })] if dice.len() == 1)
+	}
+}
+
+impl std::fmt::Display for Die {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match (self.kept, self.exploded) {
+			(true, false) => write!(f, "{}", self.value),
+			(true, true) => write!(f, "{}!", self.value),
+			(false, false) => write!(f, "~~{}~~", self.value),
+			(false, true) => write!(f, "~~{}!~~", self.value),
+		}
+	}
+}
+
+impl std::fmt::Display for Rolled {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "`{}`: ", self.text)?;
+
+		for (i, (positive, term)) in self.terms.iter().enumerate() {
+			match (i, positive) {
+				(0, true) => (),
+				(0, false) => write!(f, "-")?,
+				(_, true) => write!(f, " + ")?,
+				(_, false) => write!(f, " - ")?,
+			}
+
+			match term {
+				RolledTerm::Dice { dice, .. } => write!(
+					f,
+					"[{}]",
+					dice.iter()
+						.map(Die::to_string)
+						.collect::<Vec<_>>()
+						.join(", ")
+				)?,
+				RolledTerm::Constant(n) => write!(f, "{}", n)?,
+			}
+		}
+
+		write!(f, " = **{}**", self.total)
+	}
+}
+
+#[test]
+fn dice_notation() -> GovanResult<()> {
+	let rolls = "3d6+2, 4d6kh3, d%, d20adv, 2d6!, 20"
+		.parse::<Expressions>()?
+		.roll();
+
+	assert_eq!(rolls.len(), 6);
+	assert!(rolls[0].total >= 5.into() && rolls[0].total <= 20.into());
+	assert!(matches!(&rolls[1].terms[0].1, RolledTerm::Dice { dice, .. }
+		if dice.len() == 4 && dice.iter().filter(|d| d.kept).count() == 3));
+	assert!(matches!(&rolls[2].terms[0].1, RolledTerm::Dice { dice, .. }
+		if dice[0].value >= 1.into() && dice[0].value <= 100.into()));
+	assert!(matches!(&rolls[3].terms[0].1, RolledTerm::Dice { dice, .. } if dice.len() == 2));
+	assert!(matches!(&rolls[4].terms[0].1, RolledTerm::Dice { dice, .. }
+		if dice.iter().filter(|d| !d.exploded).count() == 2));
+	assert!(rolls[5].is_simple());
+
+	for bad in [
+		"", "d", "0d6", "d0", "0", "-5", "3d6kh4", "2d20adv", "d1!", "1d6x", "101d6",
+	] {
+		assert!(
+			bad.parse::<Expressions>().is_err(),
+			"{} should not parse",
+			bad
+		);
+	}
+
+	Ok(())
+}
@@ -12,6 +12,7 @@ pub struct Handlebar<'a> {
 impl<'a> Handlebar<'a> {
 	const ALWAYS_SUNNY: &'static str = "always_sunny";
 	const FAKE_TWITTER: &'static str = "fake_twitter";
+	const DIGEST: &'static str = "digest";
 
 	pub fn new() -> GovanResult<Handlebar<'a>> {
 		use crate::data::config;
@@ -32,6 +33,13 @@ impl<'a> Handlebar<'a> {
 				.join(config::FAKE_TWITTER_HBS),
 		)?;
 
+		handlebar.register_template_file(
+			Self::DIGEST,
+			Path::new(config::RESOURCE_PATH)
+				.join(config::HTML_DIR)
+				.join(config::DIGEST_HBS),
+		)?;
+
 		Ok(Handlebar { handlebar })
 	}
 
@@ -42,6 +50,10 @@ impl<'a> Handlebar<'a> {
 	pub fn twitter(&self, data: TweetData) -> GovanResult<String> {
 		Ok(self.handlebar.render(Self::FAKE_TWITTER, &data)?)
 	}
+
+	pub fn digest(&self, data: DigestData) -> GovanResult<String> {
+		Ok(self.handlebar.render(Self::DIGEST, &data)?)
+	}
 }
 
 lazy_static! {
@@ -109,3 +121,28 @@ pub struct TweetMoreData {
 pub struct AlwaysSunnyData {
 	pub text: String,
 }
+
+#[derive(serde::Serialize)]
+pub struct DigestData {
+	pub hall: String,
+	pub period: String,
+	pub entries: usize,
+	pub top: Option<DigestEntryData>,
+	pub authors: Vec<DigestRankData>,
+	pub reactors: Vec<DigestRankData>,
+}
+
+#[derive(serde::Serialize)]
+pub struct DigestEntryData {
+	pub author: String,
+	pub avatar: Option<String>,
+	pub content: String,
+	pub reactions: usize,
+	pub image: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+pub struct DigestRankData {
+	pub name: String,
+	pub count: usize,
+}
@@ -26,6 +26,14 @@ impl PinTask {
 	}
 }
 
+/// How many reactions are needed, and whose count
+#[derive(Debug, Clone, Copy)]
+pub struct Requirement<'a> {
+	pub amount: usize,
+	pub self_reactions: bool,
+	pub excluded_roles: &'a [u64],
+}
+
 pub struct ReactSafety {
 	bot_finished: AtomicBool,
 	tasks: RwLock<Vec<Option<PinTask>>>,
@@ -41,12 +49,12 @@ impl Default for ReactSafety {
 }
 
 impl ReactSafety {
-	async fn get_reactors(
+	pub async fn reactors(
 		&self,
 		ctx: &Context,
 		msg: &Message,
 		reaction: &Reaction,
-		required: usize,
+		required: &Requirement<'_>,
 	) -> GovanResult<Vec<User>> {
 		// First get the emoji for sure
 		if let ReactionType::Custom { name: None, id, .. } = reaction.emoji {
@@ -65,22 +73,46 @@ impl ReactSafety {
 				.reaction_users(&ctx, reaction.emoji.clone(), None, last)
 				.await?;
 
-			let filtered = users
-				.into_iter()
-				.filter(|x| !x.bot && x.id != msg.author.id)
-				.collect::<Vec<_>>();
-
-			if filtered.is_empty() {
+			let Some(page_last) = users.last().map(|x| x.id) else {
 				return Ok(res);
-			}
+			};
+
+			for user in users {
+				if user.bot || (!required.self_reactions && user.id == msg.author.id) {
+					continue;
+				}
 
-			res.extend(filtered);
+				if self
+					.is_excluded(ctx, msg, &user, required.excluded_roles)
+					.await
+				{
+					continue;
+				}
 
-			if res.len() > required {
+				res.push(user);
+			}
+
+			if res.len() > required.amount {
 				return Ok(res);
 			}
 
-			last = res.last().map(|x| x.id);
+			last = Some(page_last);
+		}
+	}
+
+	async fn is_excluded(&self, ctx: &Context, msg: &Message, user: &User, roles: &[u64]) -> bool {
+		if roles.is_empty() {
+			return false;
+		}
+
+		let Some(guild) = msg.guild_id else {
+			return false;
+		};
+
+		match guild.member(ctx, user.id).await {
+			Ok(member) => member.roles.iter().any(|role| roles.contains(&role.get())),
+			// Whoever left the server can't have the role anymore
+			Err(_) => false,
 		}
 	}
 
@@ -90,9 +122,9 @@ impl ReactSafety {
 		msg: MessageId,
 		channel: ChannelId,
 		reaction: &Reaction,
-		required: Option<usize>,
+		required: Option<Requirement<'_>>,
 		timeout: Option<std::time::Duration>,
-	) -> GovanResult {
+	) -> GovanResult<Vec<User>> {
 		// The only way to access this function is by locking HallSafety, so we're, well, safe
 
 		if self.bot_finished.load(Ordering::Relaxed) {
@@ -113,12 +145,14 @@ impl ReactSafety {
 			return Err(govanerror::debug!(log = "Already reacted")); // No reactions if I've already reacted
 		}
 
-		let reactors = self
-			.get_reactors(ctx, &msg, reaction, required.unwrap_or(0))
-			.await?;
+		let required = required.unwrap_or(Requirement {
+			amount: 0,
+			self_reactions: false,
+			excluded_roles: &[],
+		});
+		let reactors = self.reactors(ctx, &msg, reaction, &required).await?;
 
-		let required = required.unwrap_or(0);
-		if reactors.len() >= required {
+		if reactors.len() >= required.amount {
 			let reaction = msg.react(&ctx, reaction.emoji.clone()).await?;
 
 			if timeout.is_some() {
@@ -129,14 +163,45 @@ impl ReactSafety {
 				}));
 			}
 
-			Ok(())
+			Ok(reactors)
 		} else {
 			Err(govanerror::debug!(
-				log fmt = ("Not enough reactions: {} < {}", reactors.len(), required)
+				log fmt = ("Not enough reactions: {} < {}", reactors.len(), required.amount)
 			))
 		}
 	}
 
+	/// Takes back my reaction early once nobody else is reacting with that emoji anymore
+	pub async fn reaction_removed(
+		&self,
+		ctx: &Context,
+		reaction: &Reaction,
+		msg: &Message,
+	) -> GovanResult {
+		let others = msg
+			.reactions
+			.iter()
+			.find(|x| x.reaction_type == reaction.emoji)
+			.map_or(0, |x| x.count - x.me as u64);
+
+		if others > 0 {
+			return Ok(());
+		}
+
+		let mut finished = Vec::new();
+		for task in self.tasks.write().await.iter_mut() {
+			if task.as_ref().is_some_and(|t| {
+				t.reaction.message_id == reaction.message_id && t.reaction.emoji == reaction.emoji
+			}) {
+				finished.extend(task.take());
+			}
+		}
+
+		util::collect_async(finished.into_iter().map(|t| t.resolve(ctx))).await;
+
+		Ok(())
+	}
+
 	pub async fn cleanup(&mut self, ctx: &impl CacheHttp) {
 		let mut lock = self.tasks.write().await;
 
@@ -163,4 +163,11 @@ impl Screenshotter {
 		self.screenshot_from_html(&html, ".container", None, None)
 			.await
 	}
+
+	pub async fn digest(&self, digest_data: super::handlebars::DigestData) -> GovanResult<Vec<u8>> {
+		let html = self.handlebars.digest(digest_data)?;
+
+		self.screenshot_from_html(&html, ".digest", None, None)
+			.await
+	}
 }
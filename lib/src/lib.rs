@@ -25,6 +25,9 @@ pub async fn run(token: &str, beta: bool) {
 			args::Commands::Tournament(tourney_args) => {
 				other_utils::tournaments::tournament(token, tourney_args).await
 			}
+			args::Commands::Halls(halls_args) => {
+				other_utils::halls::halls(token, beta, halls_args).await
+			}
 		}
 	}
 }
@@ -50,6 +53,18 @@ async fn bot(token: &str, beta: bool) -> Option<Infallible> {
 	bot_data
 		.load_strings()
 		.ok_or_log("Could not load Strings")?;
+	bot_data
+		.load_rolls()
+		.ok_or_log("Could not load roll history")?;
+	bot_data
+		.load_hall_entries()
+		.ok_or_log("Could not load hall entries")?;
+	bot_data
+		.load_role_collections()
+		.ok_or_log("Could not load role collections")?;
+	bot_data
+		.load_schedule()
+		.ok_or_log("Could not load the schedule")?;
 
 	let bot = std::sync::Arc::new(Bot::new(bot_data));
 
@@ -1 +1,2 @@
+pub mod halls;
 pub mod tournaments;
@@ -0,0 +1,135 @@
+use crate::args::*;
+use crate::bot::Bot;
+use crate::data::BotData;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use serenity::gateway::ShardManager;
+use serenity::model::prelude::*;
+use serenity::prelude::*;
+
+use std::sync::Arc;
+
+async fn run_command(ctx: &Context, bot: &Bot, args: &HallsArgs) -> anyhow::Result<()> {
+	match &args.command {
+		HallsCommand::Backfill(args) => {
+			println!("Rebuilding the halls of {}", args.guild);
+
+			let report = bot
+				.backfill_halls(ctx, args.guild, true)
+				.await
+				.map_err(|e| anyhow!("Could not backfill: {:?}", e))?;
+
+			println!("Rebuilt {} entries", report.entries);
+
+			if report.unknown > 0 {
+				println!(
+					"{} posts were left out, their channel has more than one hall",
+					report.unknown
+				);
+			}
+
+			if report.deleted.is_empty() {
+				println!("No originals were deleted");
+			} else {
+				println!("{} originals were deleted:", report.deleted.len());
+				for entry in report.deleted.iter() {
+					println!(
+						"  {} in {}: https://discord.com/channels/{}/{}/{}",
+						entry.message,
+						entry.hall,
+						entry.guild,
+						entry.hall_channel,
+						entry.hall_message
+					);
+				}
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn load(beta: bool) -> anyhow::Result<BotData> {
+	let mut data = BotData::new(beta);
+
+	data.load_servers()
+		.map_err(|e| anyhow!("Could not load servers: {:?}", e))?;
+	data.load_hall_entries()
+		.map_err(|e| anyhow!("Could not load hall entries: {:?}", e))?;
+
+	Ok(data)
+}
+
+pub async fn halls(token: &str, beta: bool, args: HallsArgs) {
+	let data = match load(beta) {
+		Ok(data) => data,
+		Err(e) => {
+			println!("{}", e);
+			return;
+		}
+	};
+
+	let intents = GatewayIntents::GUILDS
+		| GatewayIntents::GUILD_MESSAGES
+		| GatewayIntents::MESSAGE_CONTENT
+		| GatewayIntents::GUILD_MESSAGE_REACTIONS;
+
+	let mut client = Client::builder(token, intents)
+		.event_handler(BotEventHandler {
+			bot: Bot::new(data),
+			args,
+		})
+		.await
+		.expect("Err creating client");
+
+	{
+		let mut data = client.data.write().await;
+		data.insert::<ShardManagerContainer>(client.shard_manager.clone());
+	}
+
+	let shard_manager = client.shard_manager.clone();
+	{
+		tokio::spawn(async move {
+			tokio::signal::ctrl_c()
+				.await
+				.expect("Could not register Ctrl+C handler");
+			print!("\r");
+			shard_manager.shutdown_all().await;
+		});
+	}
+
+	if let Err(why) = client.start().await {
+		println!("Client error: {:?}", why);
+	}
+}
+
+// Setup below this point
+
+struct ShardManagerContainer;
+
+impl TypeMapKey for ShardManagerContainer {
+	type Value = Arc<ShardManager>;
+}
+
+struct BotEventHandler {
+	bot: Bot,
+	args: HallsArgs,
+}
+
+#[async_trait]
+impl EventHandler for BotEventHandler {
+	async fn ready(&self, ctx: Context, _: Ready) {
+		if let Err(e) = run_command(&ctx, &self.bot, &self.args).await {
+			println!("Error while running halls command: {:?}", e);
+		}
+
+		ctx.data
+			.read()
+			.await
+			.get::<ShardManagerContainer>()
+			.unwrap()
+			.shutdown_all()
+			.await;
+	}
+}
@@ -1,4 +1,5 @@
 use crate::args::*;
+use crate::data::halls::HallEntries;
 use crate::util::random;
 
 use anyhow::{anyhow, bail};
@@ -182,6 +183,7 @@ impl Battle {
 		round_nr: u64,
 		battle_nr: u64,
 		data: &TournamentData,
+		hall_entries: &HallEntries,
 	) -> anyhow::Result<CreateMessage> {
 		let create_embed = |entry_nr: u64, is_a: bool| async move {
 			let name = if is_a { A_EMOJI } else { B_EMOJI };
@@ -228,7 +230,7 @@ impl Battle {
 			let original_message = original_channel
 				.message(&ctx, entry.original_message)
 				.await?;
-			let dismantled_embed = dismantle_embed(&message)?;
+			let dismantled_embed = dismantle_embed(&message, hall_entries)?;
 
 			let content = CONTENT_TELEPORT.replace(&dismantled_embed.content, "");
 
@@ -331,6 +333,9 @@ lazy_static! {
 }
 
 async fn run_command(ctx: &Context, args: &TournamentArgs) -> anyhow::Result<()> {
+	let hall_entries =
+		HallEntries::load().map_err(|e| anyhow!("Could not load hall entries: {:?}", e))?;
+
 	match &args.command {
 		TournamentCommand::Create(args) => {
 			let tournament_name = &args.tournament_name;
@@ -389,8 +394,13 @@ async fn run_command(ctx: &Context, args: &TournamentArgs) -> anyhow::Result<()>
 			// serenity Discord library does not enjoy that at all
 			for msg in msgs.into_iter() {
 				entries.push(
-					TournamentDataEntry::new(ctx, &msg, dismantle_embed(&msg)?, reaction_type)
-						.await?,
+					TournamentDataEntry::new(
+						ctx,
+						&msg,
+						dismantle_embed(&msg, &hall_entries)?,
+						reaction_type,
+					)
+					.await?,
 				);
 			}
 
@@ -534,7 +544,14 @@ async fn run_command(ctx: &Context, args: &TournamentArgs) -> anyhow::Result<()>
 			let odd = current_round.battles.last().is_some_and(|b| b.b.is_none());
 
 			let msg_create_futures = current_round.battles.iter().enumerate().map(|(i, b)| {
-				b.message(ctx, tournament_name, args.round, i as u64, &tournament_data)
+				b.message(
+					ctx,
+					tournament_name,
+					args.round,
+					i as u64,
+					&tournament_data,
+					&hall_entries,
+				)
 			});
 
 			let mut msg_creates = Vec::with_capacity(current_round.battles.len());
@@ -858,7 +875,7 @@ async fn fetch_messages(
 	Ok(res)
 }
 
-fn dismantle_embed(message: &Message) -> anyhow::Result<DismantledEmbed> {
+fn dismantle_embed(message: &Message, entries: &HallEntries) -> anyhow::Result<DismantledEmbed> {
 	println!("Dismantling {}", message.id);
 
 	let Some(embed) = message.embeds.first() else {
@@ -873,14 +890,6 @@ fn dismantle_embed(message: &Message) -> anyhow::Result<DismantledEmbed> {
 		);
 	}
 
-	if embed.footer.is_none() || embed.footer.as_ref().is_some_and(|f| f.text.is_empty()) {
-		bail!(
-			"Embed from {} does not have a proper footer: {:?}",
-			message.id,
-			embed.footer
-		);
-	}
-
 	if embed.description.is_none() && embed.fields.is_empty() {
 		bail!(
 			"Embed from {} has no valid description: {:?} and {:?}",
@@ -892,25 +901,46 @@ fn dismantle_embed(message: &Message) -> anyhow::Result<DismantledEmbed> {
 
 	let color = embed.colour.unwrap_or(Color::new(0xC0FFEE));
 
-	let footer_text = embed.footer.as_ref().unwrap().text.clone();
-	let footer_groups = FOOTER_PIN.captures(&footer_text);
+	// Halls can have any footer now, so recorded posts go by their entry. Older ones all had
+	// the original message and channel in the footer
+	let (original_msg, original_channel, recorded_content) =
+		if let Some(entry) = entries.of_post(message.id.get()) {
+			let content = (!entry.content.is_empty()).then(|| entry.content.clone());
+			(entry.message, entry.channel, content)
+		} else {
+			if embed.footer.is_none() || embed.footer.as_ref().is_some_and(|f| f.text.is_empty()) {
+				bail!(
+					"Embed from {} does not have a proper footer: {:?}",
+					message.id,
+					embed.footer
+				);
+			}
 
-	if footer_groups.is_none() {
-		bail!(
-			"Footer from {} does not have the proper format: {}",
-			message.id,
-			footer_text
-		);
-	}
+			let footer_text = embed.footer.as_ref().unwrap().text.clone();
+			let footer_groups = FOOTER_PIN.captures(&footer_text);
+
+			if footer_groups.is_none() {
+				bail!(
+					"Footer from {} does not have the proper format: {}",
+					message.id,
+					footer_text
+				);
+			}
 
-	let footer_groups = footer_groups.unwrap();
-	let original_msg = footer_groups.get(1).unwrap().as_str().parse::<u64>()?;
-	let original_channel = footer_groups.get(2).unwrap().as_str().parse::<u64>()?;
+			let footer_groups = footer_groups.unwrap();
+			(
+				footer_groups.get(1).unwrap().as_str().parse::<u64>()?,
+				footer_groups.get(2).unwrap().as_str().parse::<u64>()?,
+				None,
+			)
+		};
 
-	let content = embed
-		.description
-		.as_ref()
-		.unwrap_or_else(|| &embed.fields[0].value);
+	let content = recorded_content.unwrap_or_else(|| {
+		embed
+			.description
+			.clone()
+			.unwrap_or_else(|| embed.fields[0].value.clone())
+	});
 
 	if content.is_empty() {
 		bail!("No content for embed of {}", message.id);
@@ -926,7 +956,7 @@ fn dismantle_embed(message: &Message) -> anyhow::Result<DismantledEmbed> {
 		color,
 		message: MessageId::new(original_msg),
 		channel: ChannelId::new(original_channel),
-		content: content.clone(),
+		content,
 		image: image.cloned(),
 	})
 }
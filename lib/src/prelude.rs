@@ -2,6 +2,8 @@ pub use super::util;
 pub use util::error::{self as govanerror, GovanResult};
 pub use util::logger;
 pub use util::random::RandomBag;
-pub use util::traits::{CacheGuild, MatchMap, MemberExt, MessageExt, ResultExt, RoleExt};
+pub use util::traits::{
+	CacheGuild, InteractionExt, MatchMap, MemberExt, MessageExt, ResultExt, RoleExt,
+};
 
 pub use super::data;
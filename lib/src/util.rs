@@ -76,3 +76,85 @@ pub fn role_from_id(id: RoleId, ctx: &Context) -> Option<Role> {
 	#[allow(deprecated)] // Asinine to_role_cached implementation with no alternatives
 	id.to_role_cached(&ctx.cache)
 }
+
+/// Replaces a file without ever leaving it half written: the contents go to a file next to it
+/// first, which is then moved over it
+pub async fn write_file(path: std::path::PathBuf, contents: String) -> std::io::Result<()> {
+	use tokio::io::AsyncWriteExt;
+
+	if let Some(dir) = path.parent() {
+		tokio::fs::create_dir_all(dir).await?;
+	}
+
+	let temp = path.with_extension("tmp");
+	let mut file = tokio::fs::File::create(&temp).await?;
+	file.write_all(contents.as_bytes()).await?;
+	file.sync_all().await?;
+
+	tokio::fs::rename(temp, path).await
+}
+
+/// Discord's error code for a message that doesn't exist
+pub const UNKNOWN_MESSAGE: isize = 10008;
+/// Discord's error code for a member that isn't in the guild
+pub const UNKNOWN_MEMBER: isize = 10007;
+/// Discord's error code for a role that doesn't exist
+pub const UNKNOWN_ROLE: isize = 10011;
+
+/// Whether Discord turned down a request with one of these error codes
+pub fn is_discord_error(error: &serenity::Error, codes: &[isize]) -> bool {
+	matches!(
+		error,
+		serenity::Error::Http(serenity::http::HttpError::UnsuccessfulRequest(response))
+			if codes.contains(&response.error.code)
+	)
+}
+
+/// Display name of a member, asked to Discord when they're not cached, for listing people who
+/// may have left
+pub async fn member_name(
+	cache_http: impl CacheHttp,
+	guild_id: Option<GuildId>,
+	user_id: UserId,
+) -> String {
+	if let Some(guild_id) = guild_id {
+		match guild_id.member(&cache_http, user_id).await {
+			Ok(member) => return member.display_name().to_string(),
+			Err(e) if is_discord_error(&e, &[UNKNOWN_MEMBER]) => {
+				return "Someone who left".to_string()
+			}
+			Err(e) => logger::error_fmt!("Could not get member {}: {}", user_id, e),
+		}
+	}
+
+	match user_id.to_user(&cache_http).await {
+		Ok(user) => user.global_name.unwrap_or(user.name),
+		Err(e) => {
+			logger::error_fmt!("Could not get user {}: {}", user_id, e);
+			user_id.to_string()
+		}
+	}
+}
+
+/// What someone is called when there's no telling who they are
+pub const UNKNOWN_USER: &str = "Someone unknown";
+
+/// Display names of some stored users, each looked up once like [`member_name`]. An id of 0
+/// isn't anyone and isn't looked up
+pub async fn member_names(
+	cache_http: impl CacheHttp,
+	guild_id: Option<GuildId>,
+	users: &[u64],
+) -> std::collections::HashMap<u64, String> {
+	let mut names = std::collections::HashMap::new();
+	for user in users {
+		if !names.contains_key(user) {
+			let name = match std::num::NonZeroU64::new(*user) {
+				Some(id) => member_name(&cache_http, guild_id, UserId::from(id)).await,
+				None => UNKNOWN_USER.to_string(),
+			};
+			names.insert(*user, name);
+		}
+	}
+	names
+}
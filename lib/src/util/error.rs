@@ -3,7 +3,7 @@ use crate::data;
 use either::Either::*;
 use serenity::{
 	builder::CreateMessage,
-	model::prelude::{Channel, Message},
+	model::prelude::{Channel, CommandInteraction, Message},
 	prelude::*,
 };
 
@@ -112,6 +112,16 @@ impl GovanError {
 		self.send(ctx, to, strings).await.log()
 	}
 
+	pub async fn report_interaction(
+		self,
+		ctx: &Context,
+		interaction: &CommandInteraction,
+		strings: Option<&data::Strings>,
+	) {
+		self.0.send_interaction(ctx, interaction, strings).await;
+		self.log()
+	}
+
 	pub fn into_err(self) -> anyhow::Error {
 		self.0.into()
 	}
@@ -200,6 +210,7 @@ into_govan!(
 	fantoccini::error::NewSessionError[UserMsgType::None],
 	fantoccini::error::CmdError[UserMsgType::None],
 	toml::de::Error[UserMsgType::None],
+	serde_json::Error[UserMsgType::None],
 );
 
 #[derive(thiserror::Error, Debug)]
@@ -289,32 +300,53 @@ impl GovanErrorImpl {
 			}
 		};
 
-		let to_send = match self.to_user {
+		let Some(to_send) = self.user_message(strings) else {
+			return self;
+		};
+
+		let res = send(to_send).await;
+
+		if let Err(res) = res {
+			res.log();
+		}
+
+		self
+	}
+
+	async fn send_interaction<'a>(
+		&'a self,
+		ctx: &Context,
+		interaction: &CommandInteraction,
+		strings: Option<&data::Strings>,
+	) -> &'a Self {
+		use crate::util::traits::InteractionExt;
+
+		if let Some(to_send) = self.user_message(strings) {
+			interaction.followup_report(ctx, to_send).await;
+		}
+
+		self
+	}
+
+	fn user_message(&self, strings: Option<&data::Strings>) -> Option<String> {
+		match self.to_user {
 			UserMsgType::Normal(ref to_user) => {
 				if to_user.is_empty() {
-					return self;
+					return None;
 				}
 
-				to_user.clone()
+				Some(to_user.clone())
 			}
 			UserMsgType::Random(ref r) if strings.is_some() => {
 				use crate::util::random::RandomBag;
 				let strings = strings.unwrap();
 
 				match r {
-					RandomMsgType::GenericError => strings.generic_error.pick().clone(),
+					RandomMsgType::GenericError => Some(strings.generic_error.pick().clone()),
 				}
 			}
-			_ => return self,
-		};
-
-		let res = send(to_send).await;
-
-		if let Err(res) = res {
-			res.log();
+			_ => None,
 		}
-
-		self
 	}
 
 	fn log(self) {
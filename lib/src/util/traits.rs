@@ -2,7 +2,7 @@ use super::logger;
 use crate::prelude::*;
 use async_trait::async_trait;
 
-use serenity::builder::{CreateAttachment, EditRole};
+use serenity::builder::{CreateAttachment, CreateInteractionResponseFollowup, EditRole};
 use serenity::model::prelude::*;
 use serenity::prelude::*;
 
@@ -167,6 +167,29 @@ impl MessageExt for Message {
 	}
 }
 
+#[async_trait]
+pub trait InteractionExt {
+	async fn followup_report(
+		&self,
+		cache_http: impl serenity::http::CacheHttp,
+		content: impl std::fmt::Display + Send,
+	);
+}
+
+#[async_trait]
+impl InteractionExt for CommandInteraction {
+	async fn followup_report(
+		&self,
+		cache_http: impl serenity::http::CacheHttp,
+		content: impl std::fmt::Display + Send,
+	) {
+		let followup = CreateInteractionResponseFollowup::new().content(content.to_string());
+		self.create_followup(cache_http, followup)
+			.await
+			.log_if_err(&format!("Could not follow up on interaction {}", self.id));
+	}
+}
+
 #[async_trait]
 pub trait RoleExt {
 	async fn set_icon(&mut self, ctx: &Context, url: &str) -> GovanResult;
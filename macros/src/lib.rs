@@ -1,72 +1,268 @@
 extern crate proc_macro;
 
 use proc_macro_error::{proc_macro_error, Diagnostic};
-use quote::quote;
+use quote::{quote, ToTokens};
 use syn::parse::Parse;
 use syn::punctuated::Punctuated;
 use syn::{bracketed, token, Token};
 
+#[derive(Default)]
 struct CommandArguments {
 	aliases: Option<Vec<syn::LitStr>>,
+	description: Option<syn::LitStr>,
+	usage: Option<syn::LitStr>,
+	slash: bool,
+	permission: Option<syn::Ident>,
+	cooldown: Cooldown,
+	parent: Option<syn::LitStr>,
+}
+
+/// Seconds between uses of a command, for each scope
+#[derive(Default)]
+struct Cooldown {
+	user: Option<syn::LitInt>,
+	channel: Option<syn::LitInt>,
+	guild: Option<syn::LitInt>,
+}
+
+impl Parse for Cooldown {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let mut res = Cooldown::default();
+
+		let content;
+		let _: token::Bracket = bracketed!(content in input);
+
+		while !content.is_empty() {
+			let scope = content.parse::<syn::Ident>()?;
+			content.parse::<Token![=]>()?;
+			let seconds = content.parse::<syn::LitInt>()?;
+			seconds.base10_parse::<u64>()?;
+
+			if scope == "user" {
+				res.user = Some(seconds);
+			} else if scope == "channel" {
+				res.channel = Some(seconds);
+			} else if scope == "guild" {
+				res.guild = Some(seconds);
+			} else {
+				return Err(syn::Error::new(
+					scope.span(),
+					format!(
+						"Invalid cooldown scope {}, accepted are: 'user', 'channel', 'guild'",
+						scope
+					),
+				));
+			}
+
+			if !content.is_empty() {
+				content.parse::<Token![,]>()?;
+			}
+		}
+
+		Ok(res)
+	}
+}
+
+impl ToTokens for Cooldown {
+	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+		let scope = |seconds: &Option<syn::LitInt>| {
+			seconds
+				.as_ref()
+				.map_or_else(|| quote! { None }, |s| quote! { Some(#s) })
+		};
+		let (user, channel, guild) = (scope(&self.user), scope(&self.channel), scope(&self.guild));
+
+		tokens.extend(quote! {
+			crate::data::Cooldown {
+				user: #user,
+				channel: #channel,
+				guild: #guild,
+			}
+		});
+	}
 }
 
 impl Parse for CommandArguments {
 	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-		if input.is_empty() {
-			Ok(CommandArguments { aliases: None })
-		} else {
+		let mut res = CommandArguments::default();
+
+		while !input.is_empty() {
 			let param_name = input.parse::<syn::Ident>()?;
-			if param_name != "aliases" {
+
+			if param_name == "slash" {
+				res.slash = true;
+			} else if param_name == "aliases" {
+				input.parse::<syn::Token![=]>()?;
+				let content;
+				let _: token::Bracket = bracketed!(content in input);
+
+				let aliases = Punctuated::<syn::LitStr, Token![,]>::parse_terminated(&content)?;
+				res.aliases = Some(aliases.into_iter().collect::<Vec<_>>());
+			} else if param_name == "description" {
+				input.parse::<syn::Token![=]>()?;
+				res.description = Some(input.parse::<syn::LitStr>()?);
+			} else if param_name == "usage" {
+				input.parse::<syn::Token![=]>()?;
+				res.usage = Some(input.parse::<syn::LitStr>()?);
+			} else if param_name == "permission" {
+				const PERMISSIONS: &[(&str, &str)] = &[
+					("everyone", "Everyone"),
+					("admin", "Admin"),
+					("owner", "Owner"),
+				];
+
+				input.parse::<syn::Token![=]>()?;
+				let permission = input.parse::<syn::LitStr>()?;
+				let Some((_, variant)) = PERMISSIONS.iter().find(|(p, _)| permission.value() == *p)
+				else {
+					return Err(syn::Error::new(
+						permission.span(),
+						format!(
+							"Invalid permission {}, accepted are: 'everyone', 'admin', 'owner'",
+							permission.value()
+						),
+					));
+				};
+				res.permission = Some(syn::Ident::new(variant, permission.span()));
+			} else if param_name == "cooldown" {
+				input.parse::<syn::Token![=]>()?;
+				res.cooldown = input.parse::<Cooldown>()?;
+			} else if param_name == "parent" {
+				input.parse::<syn::Token![=]>()?;
+				res.parent = Some(input.parse::<syn::LitStr>()?);
+			} else {
 				return Err(syn::Error::new(
 					param_name.span(),
 					format!(
-						"Invalid parameter name {}, accepted are: 'aliases'",
+						"Invalid parameter name {}, accepted are: 'aliases', 'description', 'usage', 'slash', 'permission', 'cooldown', 'parent'",
 						param_name
 					),
 				));
 			}
 
-			input.parse::<syn::Token![=]>()?;
-			let content;
-			let _: token::Bracket = bracketed!(content in input);
-
-			let res = Punctuated::<syn::LitStr, Token![,]>::parse_terminated(&content)?;
-
 			if !input.is_empty() {
-				return Err(input.error("No more parameters allowed"));
+				input.parse::<Token![,]>()?;
 			}
-			assert!(input.is_empty(), "No more parameters allowed: {}", input);
+		}
 
-			Ok(CommandArguments {
-				aliases: Some(res.into_iter().collect::<Vec<_>>()),
-			})
+		if res.slash && res.description.is_none() {
+			return Err(input.error("Slash commands need a description"));
 		}
+
+		Ok(res)
 	}
 }
 
-// From https://stackoverflow.com/a/71482073
-fn transform_params(params: Punctuated<syn::FnArg, syn::token::Comma>) -> syn::Expr {
-	// 1. Filter the params, so that only typed arguments remain
-	// 2. Extract the ident (in case the pattern type is ident)
-	let idents = params.iter().filter_map(|param| {
-		if let syn::FnArg::Typed(pat_type) = param {
-			if let syn::Pat::Ident(pat_ident) = *pat_type.pat.clone() {
-				return Some(pat_ident.ident);
+/// What a parameter of a command function is, decided by its type
+enum Parameter {
+	Context,
+	Invocation,
+	Bot,
+	Arguments,
+	Typed {
+		name: String,
+		ty: Box<syn::Type>,
+		optional: bool,
+		description: Option<syn::LitStr>,
+		suggestions: Vec<syn::LitStr>,
+	},
+}
+
+fn last_segment(ty: &syn::Type) -> Option<&syn::PathSegment> {
+	match ty {
+		syn::Type::Path(path) => path.path.segments.last(),
+		syn::Type::Reference(reference) => last_segment(&reference.elem),
+		_ => None,
+	}
+}
+
+impl Parameter {
+	fn classify(pat_type: &mut syn::PatType) -> syn::Result<Parameter> {
+		let segment = last_segment(&pat_type.ty).map(|s| s.ident.to_string());
+		let is_reference = matches!(*pat_type.ty, syn::Type::Reference(_));
+
+		match segment.as_deref() {
+			Some("Context") if is_reference => return Ok(Parameter::Context),
+			Some("Invocation") if is_reference => return Ok(Parameter::Invocation),
+			Some("Bot") if is_reference => return Ok(Parameter::Bot),
+			Some("Arguments") if !is_reference => return Ok(Parameter::Arguments),
+			_ => (),
+		}
+
+		let syn::Pat::Ident(pat_ident) = &*pat_type.pat else {
+			return Err(syn::Error::new_spanned(
+				&pat_type.pat,
+				"Command arguments need a plain name",
+			));
+		};
+
+		let mut description = None;
+		let mut suggestions = vec![];
+		let mut error = None;
+
+		// These are ours, the compiler doesn't know what to do with them
+		pat_type.attrs.retain(|attr| {
+			let res = if attr.path().is_ident("description") {
+				attr.meta
+					.require_name_value()
+					.and_then(|nv| syn::parse2::<syn::LitStr>(nv.value.to_token_stream()))
+					.map(|lit| description = Some(lit))
+			} else if attr.path().is_ident("suggest") {
+				attr.meta
+					.require_name_value()
+					.and_then(|nv| syn::parse2::<syn::ExprArray>(nv.value.to_token_stream()))
+					.and_then(|array| {
+						array
+							.elems
+							.into_iter()
+							.map(|e| syn::parse2::<syn::LitStr>(e.to_token_stream()))
+							.collect::<syn::Result<Vec<_>>>()
+					})
+					.map(|lits| suggestions = lits)
+			} else {
+				return true;
+			};
+
+			if let Err(e) = res {
+				error.get_or_insert(e);
 			}
+			false
+		});
+
+		if let Some(e) = error {
+			return Err(e);
 		}
-		None
-	});
 
-	// Add all idents to a Punctuated => param1, param2, ...
-	let mut punctuated: Punctuated<syn::Ident, token::Comma> = Punctuated::new();
-	idents.for_each(|ident| punctuated.push(ident));
+		Ok(Parameter::Typed {
+			name: pat_ident
+				.ident
+				.to_string()
+				.trim_start_matches('_')
+				.to_string(),
+			ty: pat_type.ty.clone(),
+			optional: last_segment(&pat_type.ty).is_some_and(|s| s.ident == "Option"),
+			description,
+			suggestions,
+		})
+	}
+}
+
+/// Turns every lifetime into `'static`, so the type can be named outside of the function
+struct StaticLifetimes;
+
+impl syn::visit_mut::VisitMut for StaticLifetimes {
+	fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+		*lifetime = syn::Lifetime::new("'static", lifetime.span());
+	}
 
-	// Generate expression from Punctuated (and wrap with parentheses)
-	let transformed_params = syn::parse_quote!((#punctuated));
-	transformed_params
+	fn visit_type_reference_mut(&mut self, reference: &mut syn::TypeReference) {
+		if reference.lifetime.is_none() {
+			reference.lifetime = Some(syn::Lifetime::new("'static", reference.and_token.span));
+		}
+		syn::visit_mut::visit_type_reference_mut(self, reference);
+	}
 }
 
-// TODO Eventually maybe add type checking?
 #[proc_macro_error]
 #[proc_macro_attribute]
 pub fn command(
@@ -74,7 +270,7 @@ pub fn command(
 	item: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
 	let args = syn::parse_macro_input!(args as CommandArguments);
-	let input = syn::parse_macro_input!(item as syn::ItemFn);
+	let mut input = syn::parse_macro_input!(item as syn::ItemFn);
 
 	if input.sig.asyncness.is_none() {
 		Diagnostic::spanned(
@@ -106,27 +302,167 @@ pub fn command(
 		),
 	};
 
+	let mut parameters = vec![];
+	for input in input.sig.inputs.iter_mut() {
+		if let syn::FnArg::Typed(pat_type) = input {
+			match Parameter::classify(pat_type) {
+				Ok(parameter) => parameters.push(parameter),
+				Err(e) => return e.into_compile_error().into(),
+			}
+		}
+	}
+
+	let typed = parameters
+		.iter()
+		.filter_map(|p| match p {
+			Parameter::Typed {
+				name,
+				ty,
+				optional,
+				description,
+				suggestions,
+			} => Some((name, ty, *optional, description, suggestions)),
+			_ => None,
+		})
+		.collect::<Vec<_>>();
+
+	if let Some((name, ..)) = typed
+		.iter()
+		.skip_while(|(_, _, optional, ..)| !optional)
+		.find(|(_, _, optional, ..)| !optional)
+	{
+		Diagnostic::spanned(
+			input.sig.ident.span(),
+			proc_macro_error::Level::Error,
+			format!("Required argument {} must come before optional ones", name),
+		)
+		.emit();
+		return quote! { #input }.into();
+	}
+
+	if args.slash {
+		if let Some((name, ..)) = typed.iter().find(|(.., d, _)| d.is_none()) {
+			Diagnostic::spanned(
+				input.sig.ident.span(),
+				proc_macro_error::Level::Error,
+				format!(
+					"Argument {} needs a #[description] to be a slash option",
+					name
+				),
+			)
+			.emit();
+			return quote! { #input }.into();
+		}
+	}
+
 	let aliases = args.aliases.unwrap_or(vec![]);
+	let description = args
+		.description
+		.map_or_else(|| quote! { "" }, |d| quote! { #d });
+	let slash = args.slash;
+	let permission = args
+		.permission
+		.unwrap_or_else(|| syn::Ident::new("Everyone", proc_macro2::Span::call_site()));
+	let cooldown = &args.cooldown;
+
+	// Subcommands are used after their parent, so that's part of their usage
+	let path = match &args.parent {
+		Some(parent) => format!("{} {}", parent.value(), function_name_str),
+		None => function_name_str.clone(),
+	};
+	let parent = args
+		.parent
+		.as_ref()
+		.map_or_else(|| quote! { None }, |p| quote! { Some(#p) });
+
+	let usage = match args.usage {
+		Some(usage) => format!("{} {}", path, usage.value()),
+		None => std::iter::once(path)
+			.chain(typed.iter().map(|(name, _, optional, ..)| {
+				if *optional {
+					format!("[{}]", name)
+				} else {
+					format!("<{}>", name)
+				}
+			}))
+			.collect::<Vec<_>>()
+			.join(" "),
+	};
+
+	let options = typed
+		.iter()
+		.map(|(name, ty, optional, description, suggestions)| {
+			let mut ty = (**ty).clone();
+			syn::visit_mut::VisitMut::visit_type_mut(&mut StaticLifetimes, &mut ty);
+			let description = description
+				.as_ref()
+				.map_or_else(|| quote! { "" }, |d| quote! { #d });
+			let required = !optional;
+
+			quote! {
+				crate::commands::commander::SlashOption {
+					name: #name,
+					description: #description,
+					kind: <#ty as crate::commands::commander::FromArguments>::KIND,
+					required: #required,
+					suggestions: &[#(#suggestions),*],
+				}
+			}
+		})
+		.collect::<Vec<_>>();
+
+	let mut parse_arguments = vec![];
+	let mut call_arguments = vec![];
+	for parameter in parameters.iter() {
+		match parameter {
+			Parameter::Context => call_arguments.push(quote! { __ctx }),
+			Parameter::Invocation => call_arguments.push(quote! { __msg }),
+			Parameter::Bot => call_arguments.push(quote! { __bot }),
+			Parameter::Arguments => call_arguments.push(quote! { __args }),
+			Parameter::Typed { name, .. } => {
+				let ident = quote::format_ident!("__arg_{}", parse_arguments.len());
+				parse_arguments.push(quote! {
+					let #ident = crate::commands::commander::FromArguments::from_arguments(
+						&mut __args, __ctx, __msg
+					)
+					.ok_or_else(|| {
+						crate::commands::commander::bad_argument(
+								#name,
+								<Self as crate::commands::commander::Command>::usage(),
+								__msg,
+							)
+					})?;
+				});
+				call_arguments.push(quote! { #ident });
+			}
+		}
+	}
+
+	// Commands that don't look at the arguments themselves have nothing to do with extra words
+	if !parameters.iter().any(|p| matches!(p, Parameter::Arguments)) {
+		parse_arguments.push(quote! {
+			if let Some(extra) = __args.peek() {
+				return Err(crate::commands::commander::bad_argument(
+					extra,
+					<Self as crate::commands::commander::Command>::usage(),
+					__msg,
+				));
+			}
+		});
+	}
 
 	let function_name = input.sig.ident.clone();
-	let function_generics = input.sig.generics.clone();
-	let function_parameters = input.sig.inputs.clone();
-	let as_params = transform_params(input.sig.inputs.clone());
 
 	#[rustfmt::skip]
 	let output = quote! {
-    use async_trait::async_trait;
-    use crate::util::error::GovanResult;
-    use crate::commands::commander::Command;
-
 		pub struct #type_name;
 
     impl #type_name {
       #input
     }
 
-		#[async_trait]
-		impl Command for #type_name {
+		#[async_trait::async_trait]
+		impl crate::commands::commander::Command for #type_name {
 			fn name() -> &'static str {
 				#function_name_str
 			}
@@ -135,9 +471,44 @@ pub fn command(
 				&[#(#aliases),*]
 			}
 
-      #[allow(unused_mut)]
-			async fn execute #function_generics (#function_parameters) -> GovanResult {
-				self.#function_name #as_params.await
+			fn description() -> &'static str {
+				#description
+			}
+
+			fn usage() -> &'static str {
+				#usage
+			}
+
+			fn slash() -> bool {
+				#slash
+			}
+
+			fn options() -> &'static [crate::commands::commander::SlashOption] {
+				&[#(#options),*]
+			}
+
+			fn permission() -> crate::commands::commander::Permission {
+				crate::commands::commander::Permission::#permission
+			}
+
+			fn cooldown() -> crate::data::Cooldown {
+				#cooldown
+			}
+
+			fn parent() -> Option<&'static str> {
+				#parent
+			}
+
+      #[allow(unused_mut, unused_variables)]
+			async fn execute<'a>(
+				&self,
+				__ctx: &serenity::prelude::Context,
+				__msg: &'a crate::commands::commander::Invocation<'a>,
+				mut __args: crate::commands::commander::Arguments<'a>,
+				__bot: &crate::bot::Bot,
+			) -> crate::util::error::GovanResult {
+				#(#parse_arguments)*
+				self.#function_name(#(#call_arguments),*).await
       }
 		}
 	};